@@ -147,7 +147,9 @@ fn main() -> Result<(), Infallible> {
                         HorizontalAlignment::Left => HorizontalAlignment::Center,
                         HorizontalAlignment::Center => HorizontalAlignment::Right,
                         HorizontalAlignment::Right => HorizontalAlignment::Justified,
-                        HorizontalAlignment::Justified => HorizontalAlignment::Left,
+                        HorizontalAlignment::Justified => HorizontalAlignment::Start,
+                        HorizontalAlignment::Start => HorizontalAlignment::End,
+                        HorizontalAlignment::End => HorizontalAlignment::Left,
                     }
                 }
                 ProcessedEvent::NextVertical => {
@@ -0,0 +1,81 @@
+//! # Example: Marquee plugin.
+//!
+//! This example demonstrates scrolling lines of text that are wider than the text box with the
+//! Marquee plugin.
+
+use std::{convert::Infallible, thread, time::Duration};
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::Rectangle,
+};
+use embedded_graphics_simulator::{
+    BinaryColorTheme, OutputSettingsBuilder, SimulatorDisplay, SimulatorEvent, Window,
+};
+use embedded_text::{
+    plugin::marquee::{Marquee, MarqueeMode},
+    style::{LineBreaking, TextBoxStyle},
+    TextBox,
+};
+
+fn main() -> Result<(), Infallible> {
+    let output_settings = OutputSettingsBuilder::new()
+        .theme(BinaryColorTheme::OledBlue)
+        .scale(2)
+        .build();
+    let mut window = Window::new("Marquee plugin example", &output_settings);
+
+    let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+    let style = TextBoxStyle::with_line_breaking(LineBreaking::NoWrap);
+
+    let ticker = TextBox::with_textbox_style(
+        "Wrap mode: the start of the text follows its end.",
+        Rectangle::new(Point::new(0, 10), Size::new(128, 10)),
+        character_style,
+        style,
+    )
+    .add_plugin(Marquee::new(MarqueeMode::Wrap(" +++ ")).with_pauses(60, 0));
+
+    let bouncing = TextBox::with_textbox_style(
+        "Bounce mode: /long/path/to/some/file.txt",
+        Rectangle::new(Point::new(0, 30), Size::new(128, 10)),
+        character_style,
+        style,
+    )
+    .add_plugin(
+        Marquee::new(MarqueeMode::Bounce)
+            .with_speed(2)
+            .with_pauses(60, 60),
+    );
+
+    let short = TextBox::with_textbox_style(
+        "Short text is static.",
+        Rectangle::new(Point::new(0, 50), Size::new(128, 10)),
+        character_style,
+        style,
+    )
+    .add_plugin(Marquee::new(MarqueeMode::Bounce));
+
+    'demo: loop {
+        let mut display = SimulatorDisplay::new(Size::new(128, 64));
+
+        // Every draw call moves the text.
+        ticker.draw(&mut display)?;
+        bouncing.draw(&mut display)?;
+        short.draw(&mut display)?;
+
+        window.update(&display);
+
+        for event in window.events() {
+            if event == SimulatorEvent::Quit {
+                break 'demo;
+            }
+        }
+
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
 //! Text alignment options.
 use crate::{
-    rendering::{cursor::Cursor, space_config::SpaceConfig},
+    rendering::{
+        cursor::Cursor,
+        space_config::{GlyphSpacing, SpaceConfig},
+    },
     style::LineMeasurement,
     utils::str_width,
 };
@@ -23,35 +26,52 @@ pub enum HorizontalAlignment {
 
     /// Fully justified.
     Justified,
+
+    /// Aligned to the start of the paragraph: left aligned in left-to-right paragraphs, right
+    /// aligned in right-to-left paragraphs.
+    Start,
+
+    /// Aligned to the end of the paragraph: right aligned in left-to-right paragraphs, left
+    /// aligned in right-to-left paragraphs.
+    End,
 }
 
 impl HorizontalAlignment {
     /// Calculate offset from the left side and whitespace information.
+    ///
+    /// `rtl` is the direction of the paragraph.
     pub(crate) fn place_line(
         self,
         renderer: &impl TextRenderer,
         measurement: LineMeasurement,
-    ) -> (i32, SpaceConfig) {
+        rtl: bool,
+        justification: Justification,
+        max_word_spacing: Option<u32>,
+    ) -> (i32, SpaceConfig, GlyphSpacing) {
         let space_width = str_width(renderer, " ");
         let space_config = SpaceConfig::new(space_width, None);
-        let remaining_space = measurement.max_line_width - measurement.width;
-        match self {
-            HorizontalAlignment::Left => (0, space_config),
-            HorizontalAlignment::Center => ((remaining_space as i32 + 1) / 2, space_config),
-            HorizontalAlignment::Right => (remaining_space as i32, space_config),
+        // Lines may be wider than the text box if a word is not broken.
+        let remaining_space = measurement.max_line_width.saturating_sub(measurement.width);
+        let alignment = match self {
             HorizontalAlignment::Justified => {
-                let space_count = measurement.space_count;
-                let space_info = if !measurement.last_line() && space_count != 0 {
-                    let space = remaining_space + space_count * space_width;
-                    let space_width = space / space_count;
-                    let extra_pixels = space % space_count;
-                    SpaceConfig::new(space_width, Some(extra_pixels))
-                } else {
-                    space_config
-                };
-                (0, space_info)
+                match justification.distribute(measurement, space_width, max_word_spacing) {
+                    Some((space_config, glyph_spacing)) => return (0, space_config, glyph_spacing),
+                    // Lines that can't be justified are aligned to the start of the paragraph.
+                    None => HorizontalAlignment::Start,
+                }
             }
-        }
+            alignment => alignment,
+        };
+
+        let left = match (alignment, rtl) {
+            (HorizontalAlignment::Center, _) => (remaining_space as i32 + 1) / 2,
+            (HorizontalAlignment::Right, _)
+            | (HorizontalAlignment::Start, true)
+            | (HorizontalAlignment::End, false) => remaining_space as i32,
+            _ => 0,
+        };
+
+        (left, space_config, GlyphSpacing::none())
     }
 
     pub(crate) const fn leading_spaces(self) -> bool {
@@ -60,6 +80,8 @@ impl HorizontalAlignment {
             HorizontalAlignment::Center => false,
             HorizontalAlignment::Right => false,
             HorizontalAlignment::Justified => false,
+            HorizontalAlignment::Start => true,
+            HorizontalAlignment::End => false,
         }
     }
 
@@ -68,6 +90,73 @@ impl HorizontalAlignment {
     }
 }
 
+/// Justification options.
+///
+/// Justified lines are stretched to the full width of the text box. This setting controls where
+/// the extra space is inserted.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum Justification {
+    /// The extra space is distributed between words.
+    ///
+    /// Lines without spaces are aligned to the start of the paragraph.
+    ///
+    /// This is the default setting.
+    InterWord,
+
+    /// The extra space is distributed between words, and between characters if the line
+    /// contains no spaces or the spaces would be wider than the maximum word spacing.
+    ///
+    /// This mode can be used to justify lines that consist of a single long word or CJK text.
+    InterCharacter,
+}
+
+impl Justification {
+    /// Returns the default justification mode, which is [`Justification::InterWord`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::InterWord
+    }
+
+    /// Distributes the remaining space of a line.
+    ///
+    /// Returns `None` if the line can't be justified.
+    fn distribute(
+        self,
+        measurement: LineMeasurement,
+        space_width: u32,
+        max_word_spacing: Option<u32>,
+    ) -> Option<(SpaceConfig, GlyphSpacing)> {
+        let space_count = measurement.space_count;
+        let gap_count = measurement.glyph_count.saturating_sub(1);
+        let remaining_space = measurement.max_line_width.saturating_sub(measurement.width);
+        let max_word_spacing = max_word_spacing.unwrap_or(u32::MAX).max(space_width);
+
+        let mut space_config = SpaceConfig::new(space_width, None);
+        let mut leftover = remaining_space;
+        if space_count != 0 {
+            let space = remaining_space + space_count * space_width;
+            if space / space_count <= max_word_spacing {
+                let space_config = SpaceConfig::new(space / space_count, Some(space % space_count));
+                return Some((space_config, GlyphSpacing::none()));
+            }
+
+            space_config = SpaceConfig::new(max_word_spacing, Some(0));
+            leftover -= (max_word_spacing - space_width) * space_count;
+        }
+
+        match self {
+            Justification::InterCharacter if gap_count != 0 => Some((
+                space_config,
+                GlyphSpacing::new(leftover / gap_count, leftover % gap_count, gap_count),
+            )),
+            Justification::InterCharacter if space_count != 0 => {
+                Some((space_config, GlyphSpacing::none()))
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Vertical text alignment options.
 #[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub enum VerticalAlignment {
@@ -114,3 +203,71 @@ impl VerticalAlignment {
         }
     }
 }
+
+/// Vertical alignment of inline objects.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub enum InlineAlignment {
+    /// The bottom edge of the object is placed on the baseline of the line.
+    ///
+    /// This is the default setting.
+    Baseline,
+
+    /// The object is vertically centered in the line.
+    Middle,
+}
+
+impl InlineAlignment {
+    /// Returns the default alignment, which is [`InlineAlignment::Baseline`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::Baseline
+    }
+
+    /// Returns the vertical offset of an object of the given height, relative to the top of a
+    /// line.
+    pub(crate) fn offset(self, height: u32, baseline: u32, line_height: u32) -> i32 {
+        match self {
+            InlineAlignment::Baseline => baseline as i32 - height as i32,
+            InlineAlignment::Middle => (line_height as i32 - height as i32) / 2,
+        }
+    }
+}
+
+/// Vertical position of text, relative to the baseline of the line.
+///
+/// Shifted text is combined with a smaller font to draw superscript and subscript text. The
+/// height of a line is increased to fit the shifted text.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BaselineShift {
+    /// Text is drawn on the baseline.
+    ///
+    /// This is the default setting.
+    None,
+
+    /// Text is raised by half of the ascent of the current font.
+    Superscript,
+
+    /// Text is lowered by a third of the ascent of the current font.
+    Subscript,
+
+    /// Text is raised by the given number of pixels. Negative values lower the text.
+    Pixels(i32),
+}
+
+impl BaselineShift {
+    /// Returns the default setting, which is [`BaselineShift::None`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::None
+    }
+
+    /// Returns the distance the text is raised by, given the ascent of the current font.
+    pub(crate) fn pixels(self, ascent: u32) -> i32 {
+        match self {
+            BaselineShift::None => 0,
+            BaselineShift::Superscript => ascent as i32 / 2,
+            BaselineShift::Subscript => -(ascent as i32 / 3),
+            BaselineShift::Pixels(pixels) => pixels,
+        }
+    }
+}
@@ -8,9 +8,9 @@ use embedded_graphics::{
 };
 
 use crate::{
-    alignment::HorizontalAlignment,
+    alignment::{HorizontalAlignment, Justification},
     rendering::test::{assert_rendered, assert_styled_rendered},
-    style::{TextBoxStyle, TextBoxStyleBuilder},
+    style::{ParagraphBreaking, TextBoxStyle, TextBoxStyleBuilder},
     utils::test::size_for,
     TextBox,
 };
@@ -346,3 +346,201 @@ fn tab_rendering() {
         ],
     );
 }
+
+#[test]
+fn total_fit_paragraph_breaking() {
+    assert_styled_rendered(
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .paragraph_breaking(ParagraphBreaking::TotalFit {
+                max_breakpoints: 100,
+            })
+            .build(),
+        "at a b c quick brown",
+        size_for(&FONT_6X9, 8, 3),
+        &[
+            "................................................",
+            "........#..................................#....",
+            "........#..................................#....",
+            "..###..###................###..............###..",
+            ".#..#...#................#..#..............#..#.",
+            ".#..#...#.#..............#..#..............#..#.",
+            "..###....#................###..............###..",
+            "................................................",
+            "................................................",
+            "................................................",
+            "................................#..........#....",
+            "...........................................#....",
+            "..###...............###..#..#..##.....###..#.#..",
+            ".#.................#..#..#..#...#....#.....##...",
+            ".#.................#..#..#..#...#....#.....#.#..",
+            "..###...............###...###..###....###..#..#.",
+            "......................#.........................",
+            "......................#.........................",
+            "..............................                  ",
+            ".#............................                  ",
+            ".#............................                  ",
+            ".###...#.#....##..#...#..###..                  ",
+            ".#..#..##.#..#..#.#.#.#..#..#.                  ",
+            ".#..#..#.....#..#.#.#.#..#..#.                  ",
+            ".###...#......##...#.#...#..#.                  ",
+            "..............................                  ",
+            "..............................                  ",
+        ],
+    );
+}
+
+#[test]
+fn inter_character_justification() {
+    assert_styled_rendered(
+        TextBoxStyle::with_justification(Justification::InterCharacter),
+        "wordy x",
+        size_for(&FONT_6X9, 6, 2),
+        &[
+            "....................................",
+            "...........................#........",
+            "...........................#........",
+            "#...#.....##.....#.#.....###...#..#.",
+            "#.#.#....#..#....##.#...#..#...#..#.",
+            "#.#.#....#..#....#......#..#...#..#.",
+            ".#.#......##.....#.......###....###.",
+            "...............................#..#.",
+            "................................##..",
+            "......                              ",
+            "......                              ",
+            "......                              ",
+            ".#..#.                              ",
+            "..##..                              ",
+            "..##..                              ",
+            ".#..#.                              ",
+            "......                              ",
+            "......                              ",
+        ],
+    );
+}
+
+#[test]
+fn max_word_spacing_falls_back_to_start_alignment() {
+    assert_styled_rendered(
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .max_word_spacing(12)
+            .build(),
+        "a b cdefg hi jk",
+        size_for(&FONT_6X9, 8, 3),
+        &[
+            "..................                              ",
+            ".............#....                              ",
+            ".............#....                              ",
+            "..###........###..                              ",
+            ".#..#........#..#.                              ",
+            ".#..#........#..#.                              ",
+            "..###........###..                              ",
+            "..................                              ",
+            "..................                              ",
+            "................................................",
+            "..........#..........#...............#......#...",
+            "..........#.........#.#..............#..........",
+            "..###...###...##....#.....##.........###...##...",
+            ".#.....#..#..#.##..###...#..#........#..#...#...",
+            ".#.....#..#..##.....#....#..#........#..#...#...",
+            "..###...###...###...#.....###........#..#..###..",
+            "............................#...................",
+            "..........................##....................",
+            "............                                    ",
+            "...#...#....                                    ",
+            ".......#....                                    ",
+            "..##...#.#..                                    ",
+            "...#...##...                                    ",
+            "...#...#.#..                                    ",
+            "...#...#..#.                                    ",
+            ".#.#........                                    ",
+            "..#.........                                    ",
+        ],
+    );
+}
+
+#[test]
+fn max_word_spacing_with_inter_character_justification() {
+    assert_styled_rendered(
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .justification(Justification::InterCharacter)
+            .max_word_spacing(12)
+            .build(),
+        "ab c defgh",
+        size_for(&FONT_6X9, 8, 2),
+        &[
+            "................................................",
+            "................#...............................",
+            "................#...............................",
+            "..###...........###.........................###.",
+            ".#..#...........#..#.......................#....",
+            ".#..#...........#..#.......................#....",
+            "..###...........###.........................###.",
+            "................................................",
+            "................................................",
+            "..............................                  ",
+            "....#..........#.........#....                  ",
+            "....#.........#.#........#....                  ",
+            "..###...##....#.....##...###..                  ",
+            ".#..#..#.##..###...#..#..#..#.                  ",
+            ".#..#..##.....#....#..#..#..#.                  ",
+            "..###...###...#.....###..#..#.                  ",
+            "......................#.......                  ",
+            "....................##........                  ",
+        ],
+    );
+}
+
+#[test]
+fn last_line_alignment() {
+    assert_styled_rendered(
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .last_line_alignment(HorizontalAlignment::Right)
+            .build(),
+        "a b c d",
+        size_for(&FONT_6X9, 4, 2),
+        &[
+            "........................",
+            "...................#....",
+            "...................#....",
+            "..###..............###..",
+            ".#..#..............#..#.",
+            ".#..#..............#..#.",
+            "..###..............###..",
+            "........................",
+            "........................",
+            "      ..................",
+            "      ................#.",
+            "      ................#.",
+            "      ..###.........###.",
+            "      .#...........#..#.",
+            "      .#...........#..#.",
+            "      ..###.........###.",
+            "      ..................",
+            "      ..................",
+        ],
+    );
+}
+
+#[test]
+fn justified_last_line() {
+    assert_styled_rendered(
+        TextBoxStyle::with_last_line_alignment(HorizontalAlignment::Justified),
+        "a b",
+        size_for(&FONT_6X9, 4, 1),
+        &[
+            "........................",
+            "...................#....",
+            "...................#....",
+            "..###..............###..",
+            ".#..#..............#..#.",
+            ".#..#..............#..#.",
+            "..###..............###..",
+            "........................",
+            "........................",
+        ],
+    );
+}
@@ -10,8 +10,8 @@ use embedded_graphics::{
 
 use crate::{
     alignment::HorizontalAlignment,
-    rendering::test::assert_rendered,
-    style::{TextBoxStyle, TextBoxStyleBuilder},
+    rendering::test::{assert_rendered, assert_styled_rendered},
+    style::{ParagraphBreaking, TextBoxStyle, TextBoxStyleBuilder},
     utils::test::size_for,
     TextBox,
 };
@@ -389,3 +389,43 @@ fn trailing_spaces() {
         "..............................",
     ]);
 }
+
+#[test]
+fn total_fit_paragraph_breaking() {
+    assert_styled_rendered(
+        TextBoxStyle::with_paragraph_breaking(ParagraphBreaking::TotalFit {
+            max_breakpoints: 100,
+        }),
+        "at a b c quick brown",
+        size_for(&FONT_6X9, 8, 3),
+        &[
+            "....................................      ",
+            "........#......................#....      ",
+            "........#......................#....      ",
+            "..###..###..........###........###..      ",
+            ".#..#...#..........#..#........#..#.      ",
+            ".#..#...#.#........#..#........#..#.      ",
+            "..###....#..........###........###..      ",
+            "....................................      ",
+            "....................................      ",
+            "..........................................",
+            "..........................#..........#....",
+            ".....................................#....",
+            "..###.........###..#..#..##.....###..#.#..",
+            ".#...........#..#..#..#...#....#.....##...",
+            ".#...........#..#..#..#...#....#.....#.#..",
+            "..###.........###...###..###....###..#..#.",
+            "................#.........................",
+            "................#.........................",
+            "..............................            ",
+            ".#............................            ",
+            ".#............................            ",
+            ".###...#.#....##..#...#..###..            ",
+            ".#..#..##.#..#..#.#.#.#..#..#.            ",
+            ".#..#..#.....#..#.#.#.#..#..#.            ",
+            ".###...#......##...#.#...#..#.            ",
+            "..............................            ",
+            "..............................            ",
+        ],
+    );
+}
@@ -93,6 +93,14 @@
 //!
 //! * `plugin` (*experimental*): allows implementing custom plugins.
 //! * `ansi` (default enabled): enables ANSI sequence support using the `Ansi` plugin.
+//! * `linebreak`: enables line breaking according to the Unicode Line Breaking Algorithm.
+//! * `hyphenation`: enables automatic hyphenation of English text using the `Hyphenation` plugin.
+//! * `hyphenation-de`: adds German hyphenation patterns to the `Hyphenation` plugin.
+//! * `grapheme`: prevents breaking words inside extended grapheme clusters, e.g. between a letter
+//!   and a combining accent.
+//! * `bidi`: enables bidirectional text support, e.g. Hebrew or Arabic text mixed with left-to-right
+//!   text. Only a subset of the Unicode Bidirectional Algorithm is implemented, see
+//!   `TextDirection` for its limits.
 //!
 //! [embedded-graphics]: https://github.com/embedded-graphics/embedded-graphics/
 //! [the embedded-graphics simulator]: https://github.com/embedded-graphics/embedded-graphics/tree/master/simulator
@@ -117,7 +125,7 @@ mod utils;
 use crate::{
     alignment::{HorizontalAlignment, VerticalAlignment},
     plugin::{NoPlugin, PluginMarker as Plugin, PluginWrapper},
-    style::{HeightMode, TabSize, TextBoxStyle},
+    style::{BoxDecoration, HeightMode, Padding, TabSize, TextBoxStyle},
 };
 use embedded_graphics::{
     geometry::{Dimensions, Point},
@@ -204,6 +212,9 @@ pub use crate::{
 /// offset is applied after all vertical measurements and alignments. This can be useful to scroll
 /// text in a fixed text box. Setting a positive value moves the text down.
 ///
+/// Similarly, the [`set_horizontal_offset`] method moves the text horizontally. Combined with
+/// [`LineBreaking::NoWrap`], this can be used to scroll lines that are wider than the text box.
+///
 /// Residual text
 /// -------------
 ///
@@ -212,6 +223,8 @@ pub use crate::{
 ///
 /// [`draw`]: embedded_graphics::Drawable::draw()
 /// [`set_vertical_offset`]: TextBox::set_vertical_offset()
+/// [`set_horizontal_offset`]: TextBox::set_horizontal_offset()
+/// [`LineBreaking::NoWrap`]: crate::style::LineBreaking::NoWrap
 /// [`add_plugin`]: TextBox::add_plugin()
 /// [`take_plugins`]: TextBox::take_plugins()
 /// [`embedded-graphics` documentation]: https://docs.rs/embedded-graphics/0.7.1/embedded_graphics/text/index.html
@@ -236,6 +249,17 @@ where
     /// Vertical offset applied to the text just before rendering.
     pub vertical_offset: i32,
 
+    /// Horizontal offset applied to the text just before rendering.
+    pub horizontal_offset: i32,
+
+    /// Background and border drawn behind the text.
+    pub decoration: BoxDecoration<S::Color>,
+
+    /// Additional character styles that can be selected in the text, see [`with_fonts`].
+    ///
+    /// [`with_fonts`]: TextBox::with_fonts
+    pub fonts: &'a [S],
+
     plugin: PluginWrapper<'a, M, S::Color>,
 }
 
@@ -264,6 +288,9 @@ where
             character_style,
             style: textbox_style,
             vertical_offset: 0,
+            horizontal_offset: 0,
+            decoration: BoxDecoration::new(),
+            fonts: &[],
             plugin: PluginWrapper::new(NoPlugin::new()),
         };
 
@@ -388,6 +415,9 @@ where
             character_style: self.character_style,
             style: self.style,
             vertical_offset: self.vertical_offset,
+            horizontal_offset: self.horizontal_offset,
+            decoration: self.decoration,
+            fonts: self.fonts,
             plugin: PluginWrapper::new(Chain::new(plugin)),
         };
         styled.style.height_mode.apply(&mut styled);
@@ -414,6 +444,9 @@ where
             character_style: self.character_style,
             style: self.style,
             vertical_offset: self.vertical_offset,
+            horizontal_offset: self.horizontal_offset,
+            decoration: self.decoration,
+            fonts: self.fonts,
             plugin: PluginWrapper::new(parent.append(plugin)),
         };
         styled.style.height_mode.apply(&mut styled);
@@ -477,14 +510,22 @@ where
     #[inline]
     fn fit_height_limited(&mut self, max_height: u32) -> &mut Self {
         // Measure text given the width of the textbox
+        let padding = self.insets();
         let text_height = self
             .style
-            .measure_text_height_impl(
+            .measure_text_impl(
                 self.plugin.clone(),
                 &self.character_style,
+                self.fonts,
                 self.text,
-                self.bounding_box().size.width,
+                self.bounding_box()
+                    .size
+                    .width
+                    .saturating_sub(padding.horizontal()),
             )
+            .height;
+        let text_height = text_height
+            .saturating_add(padding.vertical())
             .min(max_height)
             .min(i32::MAX as u32);
 
@@ -494,6 +535,73 @@ where
         self
     }
 
+    /// Returns the space between the edges of the bounding box and the text.
+    pub(crate) fn insets(&self) -> Padding {
+        let border = self.decoration.border_width();
+        let padding = self.style.padding;
+
+        Padding::new(
+            padding.top.saturating_add(border),
+            padding.right.saturating_add(border),
+            padding.bottom.saturating_add(border),
+            padding.left.saturating_add(border),
+        )
+    }
+
+    /// Sets the background and border of the [`TextBox`].
+    ///
+    /// The text is moved inside the border, and the height of the text box is adjusted according
+    /// to the height mode.
+    #[inline]
+    pub fn with_decoration(mut self, decoration: BoxDecoration<S::Color>) -> Self {
+        self.decoration = decoration;
+        self.style.height_mode.apply(&mut self);
+
+        self
+    }
+
+    /// Registers additional character styles that can be used in the text.
+    ///
+    /// The fonts are selected in-band, e.g. using the alternative font codes of the `Ansi`
+    /// plugin. The height of each line fits the tallest font used in it, and the glyphs of
+    /// different fonts are aligned to a shared baseline.
+    ///
+    /// The height of the text box is adjusted according to the height mode.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_graphics::{
+    /// #     mock_display::MockDisplay,
+    /// #     mono_font::{ascii::{FONT_10X20, FONT_6X10}, MonoTextStyle},
+    /// #     pixelcolor::BinaryColor,
+    /// #     prelude::*,
+    /// #     primitives::Rectangle,
+    /// # };
+    /// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+    /// # #[cfg(feature = "ansi")]
+    /// # {
+    /// use embedded_text::{plugin::ansi::Ansi, TextBox};
+    ///
+    /// let fonts = [MonoTextStyle::new(&FONT_10X20, BinaryColor::On)];
+    /// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+    /// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 40));
+    ///
+    /// TextBox::new("\x1b[11mBig\x1b[10m small", bounding_box, character_style)
+    ///     .with_fonts(&fonts)
+    ///     .add_plugin(Ansi::new())
+    ///     .draw(&mut display)?;
+    /// # }
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    #[inline]
+    pub fn with_fonts(mut self, fonts: &'a [S]) -> Self {
+        self.fonts = fonts;
+        self.style.height_mode.apply(&mut self);
+
+        self
+    }
+
     /// Sets the vertical text offset.
     ///
     /// Vertical offset changes the vertical position of the displayed text within the bounding box.
@@ -503,4 +611,14 @@ where
         self.vertical_offset = offset;
         self
     }
-}
\ No newline at end of file
+
+    /// Sets the horizontal text offset.
+    ///
+    /// Horizontal offset changes the horizontal position of the displayed text within the bounding
+    /// box. Setting a negative value moves the text to the left.
+    #[inline]
+    pub fn set_horizontal_offset(&mut self, offset: i32) -> &mut Self {
+        self.horizontal_offset = offset;
+        self
+    }
+}
@@ -0,0 +1,60 @@
+//! Line breaking rules for Chinese and Japanese text.
+//!
+//! Text is allowed to wrap between ideographs and kana, except where the line breaking rules
+//! (kinsoku shori) prohibit it: closing brackets, punctuation and small kana must not start a line
+//! and opening brackets must not end a line.
+
+/// Returns whether the character is a CJK ideograph, kana or CJK punctuation.
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{2E80}'..='\u{2FDF}'   // CJK radicals, Kangxi radicals
+        | '\u{3000}'..='\u{303F}' // CJK symbols and punctuation
+        | '\u{3040}'..='\u{30FF}' // Hiragana, Katakana
+        | '\u{3190}'..='\u{31FF}' // Kanbun, CJK strokes, Katakana phonetic extensions
+        | '\u{3400}'..='\u{4DBF}' // CJK unified ideographs extension A
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+        | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+        | '\u{FE30}'..='\u{FE4F}' // CJK compatibility forms
+        | '\u{FF00}'..='\u{FFEF}' // Halfwidth and fullwidth forms
+        | '\u{20000}'..='\u{3FFFF}' // Supplementary ideographic planes
+    )
+}
+
+/// Characters that must not start a line.
+fn is_line_start_prohibited(c: char) -> bool {
+    matches!(
+        c,
+        // Closing brackets
+        ')' | ']' | '}' | '»' | '’' | '”' | '〉' | '》' | '」' | '』' | '】' | '〕' | '〗' | '〙'
+            | '〛' | '〞' | '〟' | '）' | '］' | '｝' | '｠' | '｣'
+        // Punctuation
+            | ',' | '.' | ':' | ';' | '?' | '!' | '、' | '。' | '，' | '．' | '：' | '；' | '？'
+            | '！' | '・' | '･' | '｡' | '､' | '‼' | '⁇' | '⁈' | '⁉' | '…' | '‥' | '〜' | '～'
+            | '゠' | '–' | '‐'
+        // Iteration marks and prolonged sound marks
+            | '々' | '〻' | 'ゝ' | 'ゞ' | 'ヽ' | 'ヾ' | 'ー' | 'ｰ'
+        // Small kana
+            | 'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' | 'っ' | 'ゃ' | 'ゅ' | 'ょ' | 'ゎ' | 'ゕ' | 'ゖ'
+            | 'ァ' | 'ィ' | 'ゥ' | 'ェ' | 'ォ' | 'ッ' | 'ャ' | 'ュ' | 'ョ' | 'ヮ' | 'ヵ' | 'ヶ'
+            | 'ｧ'..='ｯ' | '\u{31F0}'..='\u{31FF}'
+    )
+}
+
+/// Characters that must not end a line.
+fn is_line_end_prohibited(c: char) -> bool {
+    [
+        '(', '[', '{', '«', '‘', '“', '〈', '《', '「', '『', '【', '〔', '〖', '〘', '〚', '〝',
+        '（', '［', '｛', '｟', '｢',
+    ]
+    .contains(&c)
+}
+
+/// Returns whether the line may be broken between the two characters.
+pub(crate) fn is_break_allowed(before: char, after: char) -> bool {
+    (is_cjk(before) || is_cjk(after))
+        && !is_line_start_prohibited(after)
+        && !is_line_end_prohibited(before)
+}
+
+/// Punctuation that may hang into the right margin instead of being moved to the next line.
+pub(crate) const HANGING_PUNCTUATION: [char; 6] = ['、', '。', '，', '．', '､', '｡'];
@@ -17,12 +17,23 @@
 //! );
 //! ```
 use core::{marker::PhantomData, str::Chars};
-use embedded_graphics::{prelude::PixelColor, text::DecorationColor};
+use embedded_graphics::{geometry::Size, prelude::PixelColor, text::DecorationColor};
+
+use crate::{
+    alignment::{BaselineShift, InlineAlignment},
+    style::LineBreaking,
+};
+
+pub(crate) mod cjk;
+
+/// Placeholder for inline objects in the text.
+pub(crate) const OBJECT_REPLACEMENT_CHARACTER: &str = "\u{FFFC}";
 
 /// Change text style.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum ChangeTextStyle<C> {
-    /// Reset text style. Disables decoration, removes background color and sets a default text color.
+    /// Reset text style. Selects the character style of the text box, moves the text back to the
+    /// baseline, disables decoration, removes background color and sets a default text color.
     Reset,
 
     /// Change text color. `None` means transparent.
@@ -36,6 +47,19 @@ pub enum ChangeTextStyle<C> {
 
     /// Change color of strikethrough decoration.
     Strikethrough(DecorationColor<C>),
+
+    /// Change font. `None` selects the character style of the text box, `Some(n)` selects the
+    /// `n`th font registered using [`TextBox::with_fonts`].
+    ///
+    /// The selected font replaces the whole character style, including its colors and decorations.
+    ///
+    /// [`TextBox::with_fonts`]: crate::TextBox::with_fonts
+    Font(Option<usize>),
+
+    /// Move the baseline of the following text, e.g. to draw superscript or subscript text.
+    ///
+    /// The shift is applied to the fonts selected later, until it is changed again.
+    BaselineShift(BaselineShift),
 }
 
 /// A text token
@@ -69,6 +93,38 @@ pub enum Token<'a, C> {
         /// True to draw over the area of movement with the background color.
         draw_background: bool,
     },
+
+    /// An object that is placed in the text like an unbreakable word, e.g. an icon.
+    ///
+    /// The object is drawn by the plugin that produced the token. Plugins create this token using
+    /// [`Token::inline_object`].
+    InlineObject {
+        /// Position of the plugin that produced the token, set by the plugin chain.
+        #[doc(hidden)]
+        plugin: usize,
+        /// Index of the object, used by the plugin to draw it.
+        index: usize,
+        /// Size of the object.
+        size: Size,
+        /// Vertical alignment of the object in the line.
+        alignment: InlineAlignment,
+    },
+}
+
+impl<'a, C> Token<'a, C> {
+    /// Returns an inline object token.
+    ///
+    /// `index` is passed to the plugin when the object is drawn.
+    #[inline]
+    pub const fn inline_object(index: usize, size: Size, alignment: InlineAlignment) -> Self {
+        Self::InlineObject {
+            // The position of the plugin is filled in by the plugin chain.
+            plugin: 0,
+            index,
+            size,
+            alignment,
+        }
+    }
 }
 
 /// Text parser. Turns a string into a stream of [`Token`] objects.
@@ -78,6 +134,9 @@ where
     C: PixelColor,
 {
     inner: Chars<'a>,
+    line_breaking: LineBreaking,
+    /// The last word ended at a break opportunity that is not followed by whitespace.
+    pending_break: bool,
     _marker: PhantomData<C>,
 }
 
@@ -91,6 +150,22 @@ fn is_word_char(c: char) -> bool {
     (!c.is_whitespace() || c == SPEC_CHAR_NBSP) && ![SPEC_CHAR_ZWSP, SPEC_CHAR_SHY].contains(&c)
 }
 
+/// Characters that always end the current line when using [`LineBreaking::Unicode`].
+#[cfg(feature = "linebreak")]
+fn is_mandatory_break_char(c: char) -> bool {
+    // \n and \r are handled separately
+    ['\u{b}', '\u{c}', '\u{85}', '\u{2028}', '\u{2029}'].contains(&c)
+}
+
+#[cfg_attr(not(feature = "linebreak"), allow(unused_variables))]
+fn is_mandatory_break(line_breaking: LineBreaking, c: char) -> bool {
+    match line_breaking {
+        LineBreaking::Simple | LineBreaking::Cjk | LineBreaking::NoWrap => false,
+        #[cfg(feature = "linebreak")]
+        LineBreaking::Unicode => is_mandatory_break_char(c),
+    }
+}
+
 fn is_space_char(c: char) -> bool {
     // zero-width space breaks whitespace sequences - this works as long as
     // space handling is symmetrical (i.e. starting == ending behaviour)
@@ -107,10 +182,20 @@ where
     pub fn parse(text: &'a str) -> Self {
         Self {
             inner: text.chars(),
+            line_breaking: LineBreaking::Simple,
+            pending_break: false,
             _marker: PhantomData,
         }
     }
 
+    /// Sets the line breaking mode.
+    #[inline]
+    #[must_use]
+    pub fn with_line_breaking(mut self, line_breaking: LineBreaking) -> Self {
+        self.line_breaking = line_breaking;
+        self
+    }
+
     pub fn as_str(&self) -> &str {
         self.inner.as_str()
     }
@@ -123,6 +208,10 @@ where
             ptr_cur - ptr_start - c.len_utf8()
         };
 
+        self.split_string(string, offset)
+    }
+
+    fn split_string(&mut self, string: &'a str, offset: usize) -> &'a str {
         debug_assert!(string.is_char_boundary(offset));
 
         unsafe {
@@ -133,6 +222,61 @@ where
             string.get_unchecked(0..offset)
         }
     }
+
+    /// Returns the next word, ending at the first line break opportunity.
+    ///
+    /// Spaces that are not break opportunities (e.g. in front of closing punctuation) are part of
+    /// the word.
+    #[cfg(feature = "linebreak")]
+    fn next_unicode_word(&mut self, string: &'a str) -> &'a str {
+        let opportunity = unicode_linebreak::linebreaks(string)
+            .next()
+            .map_or(string.len(), |(idx, _)| idx);
+
+        // Characters that have their own tokens can't be part of a word.
+        let end = string
+            .char_indices()
+            .find(|&(_, c)| {
+                ['\n', '\r', '\t', SPEC_CHAR_ZWSP, SPEC_CHAR_SHY].contains(&c)
+                    || is_mandatory_break_char(c)
+            })
+            .map_or(opportunity, |(idx, _)| idx.min(opportunity));
+
+        // Trailing spaces are emitted as a separate token.
+        let word_len = string[..end].trim_end_matches(is_space_char).len();
+        if word_len == opportunity && opportunity < string.len() {
+            // Two words without any separators - we need to signal the break opportunity.
+            self.pending_break = true;
+        }
+
+        self.split_string(string, word_len)
+    }
+
+    /// Returns the next word, ending at the first line break opportunity allowed by the CJK line
+    /// breaking rules.
+    fn next_cjk_word(&mut self, string: &'a str) -> &'a str {
+        let mut chars = string.char_indices();
+        let mut prev = match chars.next() {
+            Some((_, c)) => c,
+            None => return string,
+        };
+
+        for (idx, c) in chars {
+            if !is_word_char(c) {
+                return self.split_string(string, idx);
+            }
+
+            if cjk::is_break_allowed(prev, c) {
+                // Two words without any separators - we need to signal the break opportunity.
+                self.pending_break = true;
+                return self.split_string(string, idx);
+            }
+
+            prev = c;
+        }
+
+        self.split_string(string, string.len())
+    }
 }
 
 impl<'a, C> Iterator for Parser<'a, C>
@@ -145,7 +289,27 @@ where
     fn next(&mut self) -> Option<Self::Item> {
         let string = self.inner.as_str();
 
+        if self.pending_break {
+            self.pending_break = false;
+            return Some(Token::Break(""));
+        }
+
         if let Some(c) = self.inner.next() {
+            #[cfg(feature = "linebreak")]
+            if self.line_breaking == LineBreaking::Unicode {
+                if is_mandatory_break_char(c) {
+                    return Some(Token::NewLine);
+                }
+
+                if is_word_char(c) {
+                    return Some(Token::Word(self.next_unicode_word(string)));
+                }
+            }
+
+            if self.line_breaking == LineBreaking::Cjk && is_word_char(c) {
+                return Some(Token::Word(self.next_cjk_word(string)));
+            }
+
             if is_word_char(c) {
                 // find the longest consecutive slice of text for a Word token
                 for c in &mut self.inner {
@@ -174,9 +338,10 @@ where
 
                     // count consecutive whitespace
                     _ => {
+                        let line_breaking = self.line_breaking;
                         let mut len = 1;
                         for c in &mut self.inner {
-                            if is_space_char(c) {
+                            if is_space_char(c) && !is_mandatory_break(line_breaking, c) {
                                 if c != SPEC_CHAR_ZWSP {
                                     len += 1;
                                 }
@@ -278,4 +443,99 @@ mod test {
             vec![Token::Word("foo"), Token::Break("-"), Token::Word("bar")],
         );
     }
+
+    #[cfg(feature = "linebreak")]
+    #[track_caller]
+    pub fn assert_unicode_tokens(text: &str, tokens: std::vec::Vec<Token<BinaryColor>>) {
+        assert_eq!(
+            Parser::parse(text)
+                .with_line_breaking(crate::style::LineBreaking::Unicode)
+                .collect::<std::vec::Vec<Token<BinaryColor>>>(),
+            tokens
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "linebreak")]
+    fn parse_unicode_break_opportunities() {
+        assert_unicode_tokens(
+            "well-known a/b",
+            vec![
+                Token::Word("well-"),
+                Token::Break(""),
+                Token::Word("known"),
+                Token::Whitespace(1, " "),
+                Token::Word("a/"),
+                Token::Break(""),
+                Token::Word("b"),
+            ],
+        );
+        assert_unicode_tokens(
+            "日本語。",
+            vec![
+                Token::Word("日"),
+                Token::Break(""),
+                Token::Word("本"),
+                Token::Break(""),
+                Token::Word("語。"),
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "linebreak")]
+    fn parse_unicode_prohibited_breaks() {
+        assert_unicode_tokens(
+            "word (example) x",
+            vec![
+                Token::Word("word"),
+                Token::Whitespace(1, " "),
+                Token::Word("(example)"),
+                Token::Whitespace(1, " "),
+                Token::Word("x"),
+            ],
+        );
+
+        // no break opportunity before the exclamation mark
+        assert_unicode_tokens("Hello !", vec![Token::Word("Hello !")]);
+    }
+
+    #[test]
+    #[cfg(feature = "linebreak")]
+    fn parse_unicode_mandatory_breaks() {
+        assert_unicode_tokens(
+            "ab\u{2028}cd \u{2029}\tx\u{ad}y",
+            vec![
+                Token::Word("ab"),
+                Token::NewLine,
+                Token::Word("cd"),
+                Token::Whitespace(1, " "),
+                Token::NewLine,
+                Token::Tab,
+                Token::Word("x"),
+                Token::Break("-"),
+                Token::Word("y"),
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_cjk_break_opportunities() {
+        assert_eq!(
+            Parser::parse("「日本」は、abc def")
+                .with_line_breaking(crate::style::LineBreaking::Cjk)
+                .collect::<std::vec::Vec<Token<BinaryColor>>>(),
+            vec![
+                Token::Word("「日"),
+                Token::Break(""),
+                Token::Word("本」"),
+                Token::Break(""),
+                Token::Word("は、"),
+                Token::Break(""),
+                Token::Word("abc"),
+                Token::Whitespace(1, " "),
+                Token::Word("def"),
+            ]
+        );
+    }
 }
@@ -96,7 +96,27 @@
 //! `Default background color` (`\x1b[49m`) codes. These codes can be used to reset colors to
 //! *transparent* (i.e. no pixels drawn for text or background).
 //!
-//! In addition, `Reset all` turns off the underlined and crossed out styles.
+//! In addition, `Reset all` turns off the underlined and crossed out styles, selects the primary
+//! font and moves the text back to the baseline.
+//!
+//! Changing fonts
+//! --------------
+//!
+//! The alternative font codes `\x1b[11m` to `\x1b[19m` select the fonts registered using
+//! [`TextBox::with_fonts`], in order. The primary font code `\x1b[10m` selects the character style
+//! of the text box again.
+//!
+//! The selected font replaces the whole character style, so the text color and decorations of the
+//! registered font are used until they are changed.
+//!
+//! [`TextBox::with_fonts`]: crate::TextBox::with_fonts
+//!
+//! Superscript and subscript
+//! -------------------------
+//!
+//! `\x1b[73m` raises the following text and `\x1b[74m` lowers it, `\x1b[75m` moves it back to the
+//! baseline. Combine these with a smaller font to draw superscript and subscript text, for
+//! example `m\x1b[11m\x1b[73m2\x1b[75m\x1b[10m`. Lines are made taller to fit the shifted text.
 //!
 //! Other supported ANSI escape codes
 //! ---------------------------------
@@ -210,7 +230,7 @@ mod test {
     use embedded_graphics::{
         mock_display::MockDisplay,
         mono_font::{
-            ascii::{FONT_6X10, FONT_6X9},
+            ascii::{FONT_10X20, FONT_4X6, FONT_6X10, FONT_6X9},
             MonoTextStyle, MonoTextStyleBuilder,
         },
         pixelcolor::{BinaryColor, Rgb888},
@@ -226,12 +246,9 @@ mod test {
         rendering::{
             cursor::LineCursor,
             line::{LineRenderState, StyledLineRenderer},
-            line_iter::{
-                test::{assert_line_elements, RenderElement},
-                LineEndType,
-            },
+            line_iter::test::{assert_line_elements, RenderElement},
         },
-        style::{HeightMode, TabSize, TextBoxStyleBuilder},
+        style::{HeightMode, TabSize, TextBoxStyleBuilder, VerticalOverdraw},
         utils::test::size_for,
         TextBox,
     };
@@ -239,7 +256,7 @@ mod test {
     #[test]
 
     fn test_measure_line_cursor_back() {
-        let character_style = MonoTextStyleBuilder::new()
+        let mut character_style = MonoTextStyleBuilder::new()
             .font(&FONT_6X9)
             .text_color(BinaryColor::On)
             .build();
@@ -253,7 +270,7 @@ mod test {
         let mut plugin = PluginWrapper::new(Ansi::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            &mut character_style,
             &mut text,
             5 * FONT_6X9.character_size.width,
         );
@@ -266,7 +283,7 @@ mod test {
         let mut plugin = PluginWrapper::new(Ansi::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            &mut character_style,
             &mut text,
             5 * FONT_6X9.character_size.width,
         );
@@ -343,16 +360,12 @@ mod test {
         );
 
         let plugin = PluginWrapper::new(Ansi::new());
-        let mut state = LineRenderState {
-            parser,
-            text_renderer,
-            end_type: LineEndType::EndOfText,
-            plugin: &plugin,
-        };
+        let mut state = LineRenderState::new(parser, text_renderer, &plugin);
         StyledLineRenderer {
             cursor,
             state: &mut state,
             style: &style,
+            ellipsis: None,
         }
         .draw(&mut display)
         .unwrap();
@@ -397,6 +410,253 @@ mod test {
         assert_eq!(3 * 9, tb.bounds.size.height);
     }
 
+    #[test]
+    fn lines_fit_the_tallest_font() {
+        let fonts = [MonoTextStyle::new(&FONT_10X20, BinaryColor::On)];
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let tb = TextBox::with_height_mode(
+            "\x1b[11mBig\x1b[10m small\nsmall",
+            Rectangle::new(Point::zero(), Size::new(150, 240)),
+            character_style,
+            HeightMode::FitToText,
+        )
+        .with_fonts(&fonts)
+        .add_plugin(Ansi::new());
+
+        assert_eq!(20 + 9, tb.bounds.size.height);
+    }
+
+    #[test]
+    fn fonts_share_baseline() {
+        let mut display = MockDisplay::new();
+
+        let fonts = [MonoTextStyle::new(&FONT_6X9, BinaryColor::On)];
+        let character_style = MonoTextStyle::new(&FONT_4X6, BinaryColor::On);
+
+        TextBox::with_height_mode(
+            "a\x1b[11mb\x1b[10mc",
+            Rectangle::new(Point::zero(), Size::new(20, 20)),
+            character_style,
+            HeightMode::FitToText,
+        )
+        .with_fonts(&fonts)
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "             ",
+            "     #       ",
+            "     #       ",
+            " ##  ###   ##",
+            "# #  #  # #  ",
+            "# #  #  # #  ",
+            " ##  ###   ##",
+        ]);
+    }
+
+    #[test]
+    fn superscript_extends_line() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let text_box = TextBox::with_height_mode(
+            "x\x1b[73m2\x1b[75mx",
+            Rectangle::new(Point::zero(), Size::new(20, 20)),
+            character_style,
+            HeightMode::FitToText,
+        )
+        .add_plugin(Ansi::new());
+
+        assert_eq!(text_box.bounds.size.height, 12);
+
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "                 ",
+            "        ##       ",
+            "       #  #      ",
+            "          #      ",
+            "         #       ",
+            "        #        ",
+            " #  #  ####  #  #",
+            "  ##          ## ",
+            "  ##          ## ",
+            " #  #        #  #",
+        ]);
+    }
+
+    #[test]
+    fn subscript_is_not_clipped() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let mut text_box = TextBox::with_height_mode(
+            "H\x1b[74m2\x1b[75mO",
+            Rectangle::new(Point::zero(), Size::new(20, 20)),
+            character_style,
+            HeightMode::Exact(VerticalOverdraw::Hidden),
+        )
+        .add_plugin(Ansi::new());
+        text_box.fit_height();
+
+        assert_eq!(text_box.bounds.size.height, 11);
+
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "                 ",
+            " #  #        ### ",
+            " #  #       #   #",
+            " ####   ##  #   #",
+            " #  #  #  # #   #",
+            " #  #     # #   #",
+            " #  #    #   ### ",
+            "        #        ",
+            "       ####      ",
+        ]);
+    }
+
+    #[test]
+    fn line_sides_use_background_at_line_edges() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::with_textbox_style(
+            "\x1b[47mab",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 1)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .alignment(HorizontalAlignment::Center)
+                .fill_line_background(true)
+                .build(),
+        )
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "......##################",
+            "......##################",
+            "......##################",
+            "......##################",
+            "......##################",
+            "......##################",
+            "......##################",
+            "......##################",
+            "......##################",
+        ]);
+    }
+
+    #[test]
+    fn line_sides_fill_tall_lines() {
+        let mut display = MockDisplay::new();
+
+        let fonts = [MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build()];
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_4X6)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::with_textbox_style(
+            "a\x1b[11mb",
+            Rectangle::new(Point::zero(), Size::new(16, 9)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .alignment(HorizontalAlignment::Center)
+                .fill_line_background(true)
+                .build(),
+        )
+        .with_fonts(&fonts)
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "................",
+            "........#.......",
+            "........#.......",
+            "....##..###.....",
+            "...#.#..#..#....",
+            "...#.#..#..#....",
+            "....##..###.....",
+            "................",
+            "................",
+        ]);
+    }
+
+    #[test]
+    fn reset_selects_primary_font() {
+        let mut display = MockDisplay::new();
+
+        let fonts = [MonoTextStyle::new(&FONT_6X9, BinaryColor::On)];
+        let character_style = MonoTextStyle::new(&FONT_4X6, BinaryColor::On);
+
+        TextBox::with_height_mode(
+            "a\x1b[11mb\x1b[0m\x1b[37mc",
+            Rectangle::new(Point::zero(), Size::new(20, 20)),
+            character_style,
+            HeightMode::FitToText,
+        )
+        .with_fonts(&fonts)
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "             ",
+            "     #       ",
+            "     #       ",
+            " ##  ###   ##",
+            "# #  #  # #  ",
+            "# #  #  # #  ",
+            " ##  ###   ##",
+        ]);
+    }
+
+    #[test]
+    fn reset_clears_baseline_shift() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        TextBox::with_height_mode(
+            "x\x1b[73m2\x1b[0m\x1b[37mx",
+            Rectangle::new(Point::zero(), Size::new(20, 20)),
+            character_style,
+            HeightMode::FitToText,
+        )
+        .add_plugin(Ansi::new())
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "                 ",
+            "        ##       ",
+            "       #  #      ",
+            "          #      ",
+            "         #       ",
+            "        #        ",
+            " #  #  ####  #  #",
+            "  ##          ## ",
+            "  ##          ## ",
+            " #  #        #  #",
+        ]);
+    }
+
     #[test]
     fn no_panic_when_word_is_broken() {
         let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
@@ -2,7 +2,7 @@
 
 use embedded_graphics::{pixelcolor::Rgb888, prelude::PixelColor, text::DecorationColor};
 
-use crate::parser::ChangeTextStyle;
+use crate::{alignment::BaselineShift, parser::ChangeTextStyle};
 
 /// List of supported SGR (Select Graphics Rendition) sequences
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -33,6 +33,12 @@ pub(crate) enum Sgr {
 
     /// Reset the background color to transparent
     DefaultBackgroundColor,
+
+    /// Select the primary font, or one of the alternative fonts
+    Font(Option<usize>),
+
+    /// Raise, lower or reset the baseline of the text
+    BaselineShift(BaselineShift),
 }
 
 impl<C: PixelColor + From<Rgb888>> From<Sgr> for ChangeTextStyle<C> {
@@ -48,6 +54,8 @@ impl<C: PixelColor + From<Rgb888>> From<Sgr> for ChangeTextStyle<C> {
             Sgr::DefaultTextColor => ChangeTextStyle::TextColor(None),
             Sgr::ChangeBackgroundColor(c) => ChangeTextStyle::BackgroundColor(Some(c.into())),
             Sgr::DefaultBackgroundColor => ChangeTextStyle::BackgroundColor(None),
+            Sgr::Font(font) => ChangeTextStyle::Font(font),
+            Sgr::BaselineShift(shift) => ChangeTextStyle::BaselineShift(shift),
         }
     }
 }
@@ -135,10 +143,15 @@ pub(crate) fn try_parse_sgr(v: &[u8]) -> Option<Sgr> {
         0 => Some(Sgr::Reset),
         4 => Some(Sgr::Underline),
         9 => Some(Sgr::CrossedOut),
+        10 => Some(Sgr::Font(None)),
+        11..=19 => Some(Sgr::Font(Some(usize::from(code - 11)))),
         24 => Some(Sgr::UnderlineOff),
         29 => Some(Sgr::NotCrossedOut),
         39 => Some(Sgr::DefaultTextColor),
         49 => Some(Sgr::DefaultBackgroundColor),
+        73 => Some(Sgr::BaselineShift(BaselineShift::Superscript)),
+        74 => Some(Sgr::BaselineShift(BaselineShift::Subscript)),
+        75 => Some(Sgr::BaselineShift(BaselineShift::None)),
         30..=37 => Some(Sgr::ChangeTextColor(standard_to_rgb(code - 30))),
         38 => {
             let color = try_parse_color(&v[1..])?;
@@ -0,0 +1,411 @@
+//! Arabic contextual shaping.
+//!
+//! Arabic letters are displayed using different glyphs depending on whether they are connected to
+//! the neighbouring letters. The [`ArabicShaping`] plugin replaces the basic Arabic letters
+//! (U+0621 - U+064A) with their isolated, initial, medial or final forms from the Arabic
+//! Presentation Forms-B block (U+FE70 - U+FEFF), so that fonts that only contain the presentation
+//! forms can display Arabic text. Lam-alef sequences are replaced by the corresponding ligatures.
+//!
+//! Shaping happens before the text is measured, so line breaking and alignment use the width of
+//! the displayed glyphs. To display Arabic text in the correct order, enable the `bidi` feature.
+//!
+//! *Note:* letters are only connected inside a single word. Letters that are separated by a style
+//! change or by another plugin are shaped as if they were at the end or start of a word.
+
+use crate::{parser::Token, plugin::Plugin};
+use embedded_graphics::prelude::PixelColor;
+
+/// How a letter connects to its neighbours.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Joining {
+    /// The letter doesn't connect to its neighbours.
+    None,
+
+    /// The letter only connects to the preceding letter.
+    Right,
+
+    /// The letter connects to both of its neighbours.
+    Dual,
+
+    /// The character connects its neighbours, but has no contextual forms (tatweel).
+    Causing,
+
+    /// The character is ignored when looking for the neighbours of a letter (e.g. vowel marks).
+    Transparent,
+}
+
+/// Contextual forms of a letter.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Form {
+    Isolated = 0,
+    Final = 1,
+    Initial = 2,
+    Medial = 3,
+}
+
+/// The joining type and the first presentation form of the letters from U+0621 to U+064A.
+///
+/// The isolated, final, initial and medial forms of each letter are consecutive characters in the
+/// Presentation Forms-B block. Letters that don't have presentation forms are listed with 0.
+const LETTERS: [(Joining, u16); 42] = [
+    (Joining::None, 0xFE80),  // hamza
+    (Joining::Right, 0xFE81), // alef with madda above
+    (Joining::Right, 0xFE83), // alef with hamza above
+    (Joining::Right, 0xFE85), // waw with hamza above
+    (Joining::Right, 0xFE87), // alef with hamza below
+    (Joining::Dual, 0xFE89),  // yeh with hamza above
+    (Joining::Right, 0xFE8D), // alef
+    (Joining::Dual, 0xFE8F),  // beh
+    (Joining::Right, 0xFE93), // teh marbuta
+    (Joining::Dual, 0xFE95),  // teh
+    (Joining::Dual, 0xFE99),  // theh
+    (Joining::Dual, 0xFE9D),  // jeem
+    (Joining::Dual, 0xFEA1),  // hah
+    (Joining::Dual, 0xFEA5),  // khah
+    (Joining::Right, 0xFEA9), // dal
+    (Joining::Right, 0xFEAB), // thal
+    (Joining::Right, 0xFEAD), // reh
+    (Joining::Right, 0xFEAF), // zain
+    (Joining::Dual, 0xFEB1),  // seen
+    (Joining::Dual, 0xFEB5),  // sheen
+    (Joining::Dual, 0xFEB9),  // sad
+    (Joining::Dual, 0xFEBD),  // dad
+    (Joining::Dual, 0xFEC1),  // tah
+    (Joining::Dual, 0xFEC5),  // zah
+    (Joining::Dual, 0xFEC9),  // ain
+    (Joining::Dual, 0xFECD),  // ghain
+    (Joining::Dual, 0),       // keheh with two dots above
+    (Joining::Dual, 0),       // keheh with three dots below
+    (Joining::Dual, 0),       // farsi yeh with inverted v
+    (Joining::Dual, 0),       // farsi yeh with two dots above
+    (Joining::Dual, 0),       // farsi yeh with three dots above
+    (Joining::Causing, 0),    // tatweel
+    (Joining::Dual, 0xFED1),  // feh
+    (Joining::Dual, 0xFED5),  // qaf
+    (Joining::Dual, 0xFED9),  // kaf
+    (Joining::Dual, 0xFEDD),  // lam
+    (Joining::Dual, 0xFEE1),  // meem
+    (Joining::Dual, 0xFEE5),  // noon
+    (Joining::Dual, 0xFEE9),  // heh
+    (Joining::Right, 0xFEED), // waw
+    (Joining::Right, 0xFEEF), // alef maksura
+    (Joining::Dual, 0xFEF1),  // yeh
+];
+
+const LAM: char = '\u{644}';
+
+/// The first code point of the UTF-8 encoded presentation forms in [`PRESENTATION_FORMS`].
+const FIRST_FORM: u32 = 0xFE80;
+
+/// UTF-8 encoded characters from U+FE80 to U+FEFC.
+///
+/// Presentation forms are returned as slices of this array, because plugins can only produce
+/// tokens that borrow static or source text.
+static PRESENTATION_FORMS: [u8; 3 * 125] = {
+    let mut bytes = [0; 3 * 125];
+    let mut i = 0;
+    while i < 125 {
+        let c = FIRST_FORM + i as u32;
+        bytes[3 * i] = 0xE0 | (c >> 12) as u8;
+        bytes[3 * i + 1] = 0x80 | ((c >> 6) & 0x3F) as u8;
+        bytes[3 * i + 2] = 0x80 | (c & 0x3F) as u8;
+        i += 1;
+    }
+    bytes
+};
+
+/// Returns the presentation form at the given code point as a string.
+fn presentation_form(c: u32) -> &'static str {
+    let start = 3 * (c - FIRST_FORM) as usize;
+    let bytes = &PRESENTATION_FORMS[start..start + 3];
+
+    // SAFETY: every 3 bytes of the array encode a single character.
+    unsafe { core::str::from_utf8_unchecked(bytes) }
+}
+
+fn joining(c: char) -> Joining {
+    match c {
+        '\u{621}'..='\u{64A}' => LETTERS[c as usize - 0x621].0,
+        '\u{610}'..='\u{61A}' | '\u{64B}'..='\u{65F}' | '\u{670}' | '\u{6D6}'..='\u{6ED}' => {
+            Joining::Transparent
+        }
+        _ => Joining::None,
+    }
+}
+
+/// Returns the first presentation form of a letter.
+fn first_form(c: char) -> Option<u32> {
+    match c {
+        '\u{621}'..='\u{64A}' => match LETTERS[c as usize - 0x621].1 {
+            0 => None,
+            first_form => Some(first_form as u32),
+        },
+        _ => None,
+    }
+}
+
+/// Returns the first presentation form of the lam-alef ligature, if `c` is an alef.
+const fn lam_alef(c: char) -> Option<u32> {
+    match c {
+        '\u{622}' => Some(0xFEF5),
+        '\u{623}' => Some(0xFEF7),
+        '\u{625}' => Some(0xFEF9),
+        '\u{627}' => Some(0xFEFB),
+        _ => None,
+    }
+}
+
+/// Arabic shaping plugin.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X10, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// # display.set_allow_overdraw(true);
+/// use embedded_text::{plugin::arabic_shaping::ArabicShaping, TextBox};
+///
+/// // A font that contains the Arabic Presentation Forms-B block should be used here.
+/// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 20));
+///
+/// TextBox::new("مرحبا", bounding_box, character_style)
+///     .add_plugin(ArabicShaping::new())
+///     .draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ArabicShaping<'a> {
+    /// The unprocessed part of the current word.
+    word: &'a str,
+
+    /// True if the previous letter connects to the next one.
+    joins_next: bool,
+
+    /// True if the last returned token is not the first piece of a word.
+    continues_word: bool,
+}
+
+impl<'a> ArabicShaping<'a> {
+    /// Returns a new plugin object.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            word: "",
+            joins_next: false,
+            continues_word: false,
+        }
+    }
+
+    /// Returns the joining type of the next letter in the current word, skipping transparent
+    /// characters.
+    fn next_joining(&self) -> Joining {
+        self.word
+            .chars()
+            .map(joining)
+            .find(|&joining| joining != Joining::Transparent)
+            .unwrap_or(Joining::None)
+    }
+
+    /// Returns the next piece of the current word.
+    fn next_piece(&mut self) -> &'a str {
+        let mut chars = self.word.chars();
+        let c = match chars.next() {
+            Some(c) => c,
+            None => return "",
+        };
+
+        let first_form = match first_form(c) {
+            Some(first_form) => first_form,
+            None => {
+                // Copy characters that aren't shaped from the source text.
+                let end = self
+                    .word
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(_, c)| first_form(c).is_some())
+                    .map_or(self.word.len(), |(idx, _)| idx);
+
+                let (piece, rest) = self.word.split_at(end);
+                self.joins_next = piece
+                    .chars()
+                    .rev()
+                    .map(joining)
+                    .find(|&joining| joining != Joining::Transparent)
+                    .map_or(self.joins_next, |joining| {
+                        matches!(joining, Joining::Dual | Joining::Causing)
+                    });
+                self.word = rest;
+
+                return piece;
+            }
+        };
+
+        let joins_previous = self.joins_next;
+
+        // Lam followed by alef is displayed as a ligature.
+        if c == LAM {
+            if let Some(ligature) = chars.next().and_then(lam_alef) {
+                self.word = &self.word[c.len_utf8() * 2..];
+                self.joins_next = false;
+
+                let form = if joins_previous {
+                    Form::Final
+                } else {
+                    Form::Isolated
+                };
+                return presentation_form(ligature + form as u32);
+            }
+        }
+
+        self.word = &self.word[c.len_utf8()..];
+
+        let letter_joining = joining(c);
+        let joins_next = letter_joining == Joining::Dual
+            && matches!(
+                self.next_joining(),
+                Joining::Right | Joining::Dual | Joining::Causing
+            );
+        self.joins_next = joins_next;
+
+        let form = match (joins_previous, joins_next) {
+            (true, true) => Form::Medial,
+            (true, false) => Form::Final,
+            (false, true) => Form::Initial,
+            (false, false) => Form::Isolated,
+        };
+        let form = match (letter_joining, form) {
+            // Right joining letters only have isolated and final forms.
+            (Joining::Right, Form::Initial) => Form::Isolated,
+            (Joining::Right, Form::Medial) => Form::Final,
+            (Joining::None, _) => Form::Isolated,
+            (_, form) => form,
+        };
+
+        presentation_form(first_form + form as u32)
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for ArabicShaping<'a> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        self.continues_word = !self.word.is_empty();
+        if self.word.is_empty() {
+            match next_token() {
+                Some(Token::Word(word)) => {
+                    self.word = word;
+                    self.joins_next = false;
+                }
+                token => return token,
+            }
+        }
+
+        Some(Token::Word(self.next_piece()))
+    }
+
+    #[inline]
+    fn continues_word(&self) -> bool {
+        self.continues_word
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::Point,
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{
+        parser::{Parser, Token},
+        plugin::{arabic_shaping::ArabicShaping, Plugin},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_shaped(text: &str, expected: &str) {
+        let mut plugin = ArabicShaping::new();
+        let mut parser = Parser::parse(text);
+
+        let result = core::iter::from_fn(|| plugin.next_token(|| parser.next()))
+            .map(|token: Token<BinaryColor>| match token {
+                Token::Word(w) => w,
+                Token::Whitespace(_, w) => w,
+                token => panic!("Unexpected token: {:?}", token),
+            })
+            .collect::<String>();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn dual_joining_letters() {
+        // beh, yeh, teh
+        assert_shaped("\u{628}\u{64A}\u{62A}", "\u{FE91}\u{FEF4}\u{FE96}");
+        assert_shaped("\u{628}", "\u{FE8F}");
+    }
+
+    #[test]
+    fn right_joining_letters_break_the_connection() {
+        // dal, alef, reh
+        assert_shaped("\u{62F}\u{627}\u{631}", "\u{FEA9}\u{FE8D}\u{FEAD}");
+        // beh, alef, beh
+        assert_shaped("\u{628}\u{627}\u{628}", "\u{FE91}\u{FE8E}\u{FE8F}");
+    }
+
+    #[test]
+    fn lam_alef_ligatures() {
+        assert_shaped("\u{644}\u{627}", "\u{FEFB}");
+        // seen, lam, alef, meem
+        assert_shaped("\u{633}\u{644}\u{627}\u{645}", "\u{FEB3}\u{FEFC}\u{FEE1}");
+        assert_shaped("\u{644}\u{623}", "\u{FEF7}");
+    }
+
+    #[test]
+    fn transparent_and_unshaped_characters() {
+        // Vowel marks don't break the connection.
+        assert_shaped("\u{628}\u{64E}\u{628}", "\u{FE91}\u{64E}\u{FE90}");
+        // Tatweel connects to the following letter.
+        assert_shaped("\u{640}\u{628}", "\u{640}\u{FE90}");
+        // Words are shaped separately.
+        assert_shaped("ab\u{628}\u{628} \u{628}1", "ab\u{FE91}\u{FE90} \u{FE8F}1");
+    }
+
+    #[test]
+    fn shaped_word_is_not_broken() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 4, 2));
+
+        let mut display = MockDisplay::new();
+        TextBox::new("a \u{628}\u{64A}\u{62A}", bounds, character_style)
+            .add_plugin(ArabicShaping::new())
+            .draw(&mut display)
+            .unwrap();
+
+        // The font doesn't contain Arabic glyphs, but the word is moved to the next line.
+        let mut expected = MockDisplay::new();
+        TextBox::new("a\n???", bounds, character_style)
+            .draw(&mut expected)
+            .unwrap();
+
+        display.assert_eq(&expected);
+    }
+}
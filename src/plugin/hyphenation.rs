@@ -0,0 +1,344 @@
+//! Automatic hyphenation.
+//!
+//! The [`Hyphenation`] plugin inserts hyphenation points into words using TeX hyphenation patterns
+//! (Liang's algorithm). When a word does not fit into the current line, it will be broken at one of
+//! these points, and a hyphen will be displayed at the end of the line.
+//!
+//! The patterns are compiled into the binary as static data. English patterns are available using
+//! the `hyphenation` feature, German patterns require the `hyphenation-de` feature.
+//!
+//! *Note:* only words that consist of alphabetic characters (surrounded by optional punctuation)
+//! and are at most 41 bytes long are hyphenated.
+
+use core::marker::PhantomData;
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{parser::Token, plugin::Plugin};
+
+/// The longest word (in bytes) that can be hyphenated without allocation.
+const MAX_WORD_LENGTH: usize = 41;
+
+/// Hyphenation pattern language.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum Language {
+    /// US English patterns.
+    English,
+
+    /// German patterns (1996 orthography).
+    #[cfg(feature = "hyphenation-de")]
+    German,
+}
+
+impl Language {
+    const fn patterns(self) -> hypher::Lang {
+        match self {
+            Language::English => hypher::Lang::English,
+            #[cfg(feature = "hyphenation-de")]
+            Language::German => hypher::Lang::German,
+        }
+    }
+
+    /// Returns the customary minimum fragment lengths for the language.
+    const fn default_fragment_lengths(self) -> (u8, u8) {
+        match self {
+            Language::English => (2, 3),
+            #[cfg(feature = "hyphenation-de")]
+            Language::German => (2, 2),
+        }
+    }
+}
+
+/// Hyphenation plugin.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X10, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// # display.set_allow_out_of_bounds_drawing(true);
+/// use embedded_text::{
+///     alignment::HorizontalAlignment,
+///     plugin::hyphenation::{Hyphenation, Language},
+///     TextBox,
+/// };
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 60));
+///
+/// TextBox::with_alignment(
+///     "Hyphenation improves justified text on narrow displays.",
+///     bounding_box,
+///     character_style,
+///     HorizontalAlignment::Justified,
+/// )
+/// .add_plugin(Hyphenation::new(Language::English))
+/// .draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Clone)]
+pub struct Hyphenation<'a, C: PixelColor> {
+    language: Language,
+    left_min: u8,
+    right_min: u8,
+
+    /// The unprocessed part of the current word.
+    word: &'a str,
+
+    /// Hyphenation points of `word`. Bit `n` is set if the word can be broken before byte `n`.
+    breaks: u64,
+
+    /// True if the previous token ended at a hyphenation point.
+    pending_break: bool,
+
+    _marker: PhantomData<C>,
+}
+
+impl<'a, C: PixelColor> Hyphenation<'a, C> {
+    /// Returns a new plugin object that uses the patterns of the given language.
+    #[inline]
+    pub const fn new(language: Language) -> Self {
+        let (left_min, right_min) = language.default_fragment_lengths();
+
+        Self {
+            language,
+            left_min,
+            right_min,
+            word: "",
+            breaks: 0,
+            pending_break: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Sets the minimum number of characters that must remain before and after a hyphenation
+    /// point.
+    #[inline]
+    pub const fn with_fragment_lengths(mut self, left: u8, right: u8) -> Self {
+        self.left_min = left;
+        self.right_min = right;
+
+        self
+    }
+
+    /// Collects the hyphenation points of `word`.
+    fn find_breaks(&self, word: &str) -> u64 {
+        if word.len() > MAX_WORD_LENGTH {
+            return 0;
+        }
+
+        // Leading and trailing punctuation are not part of the hyphenated word.
+        let is_letter = |c: char| c.is_alphabetic();
+        let start = match word.find(is_letter) {
+            Some(start) => start,
+            None => return 0,
+        };
+        let core = word[start..].trim_end_matches(|c: char| !is_letter(c));
+
+        if !core.chars().all(is_letter) {
+            return 0;
+        }
+
+        let syllables = hypher::hyphenate_bounded(
+            core,
+            self.language.patterns(),
+            self.left_min as usize,
+            self.right_min as usize,
+        );
+
+        let mut breaks = 0;
+        let mut pos = start;
+        for syllable in syllables {
+            pos += syllable.len();
+            breaks |= 1 << pos;
+        }
+
+        // The last syllable ends with the word, there's no break after it.
+        breaks & !(1 << pos)
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for Hyphenation<'a, C> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if self.pending_break {
+            self.pending_break = false;
+            return Some(Token::Break("-"));
+        }
+
+        if self.word.is_empty() {
+            match next_token() {
+                Some(Token::Word(word)) => {
+                    self.word = word;
+                    self.breaks = self.find_breaks(word);
+                }
+                token => return token,
+            }
+        }
+
+        let (syllable, rest) = if self.breaks == 0 {
+            (self.word, "")
+        } else {
+            let idx = self.breaks.trailing_zeros();
+            self.breaks >>= idx;
+            self.breaks &= !1;
+            self.pending_break = true;
+
+            self.word.split_at(idx as usize)
+        };
+
+        self.word = rest;
+
+        Some(Token::Word(syllable))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::Point,
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{
+        alignment::HorizontalAlignment,
+        parser::{Parser, Token},
+        plugin::{
+            hyphenation::{Hyphenation, Language},
+            Plugin,
+        },
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_tokens(
+        plugin: Hyphenation<'_, BinaryColor>,
+        text: &str,
+        tokens: std::vec::Vec<Token<BinaryColor>>,
+    ) {
+        let mut plugin = plugin;
+        let mut parser = Parser::parse(text);
+
+        let result = core::iter::from_fn(|| plugin.next_token(|| parser.next()))
+            .collect::<std::vec::Vec<_>>();
+
+        assert_eq!(result, tokens);
+    }
+
+    #[test]
+    fn inserts_breaks() {
+        assert_tokens(
+            Hyphenation::new(Language::English),
+            "(hyphenation), example",
+            vec![
+                Token::Word("(hy"),
+                Token::Break("-"),
+                Token::Word("phen"),
+                Token::Break("-"),
+                Token::Word("ation),"),
+                Token::Whitespace(1, " "),
+                Token::Word("ex"),
+                Token::Break("-"),
+                Token::Word("am"),
+                Token::Break("-"),
+                Token::Word("ple"),
+            ],
+        );
+    }
+
+    #[test]
+    fn respects_fragment_lengths() {
+        assert_tokens(
+            Hyphenation::new(Language::English).with_fragment_lengths(3, 4),
+            "hyphenation",
+            vec![
+                Token::Word("hyphen"),
+                Token::Break("-"),
+                Token::Word("ation"),
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hyphenation-de")]
+    fn german_patterns() {
+        assert_tokens(
+            Hyphenation::new(Language::German),
+            "Silbentrennung",
+            vec![
+                Token::Word("Sil"),
+                Token::Break("-"),
+                Token::Word("ben"),
+                Token::Break("-"),
+                Token::Word("tren"),
+                Token::Break("-"),
+                Token::Word("nung"),
+            ],
+        );
+    }
+
+    #[test]
+    fn skips_words_with_non_letters() {
+        assert_tokens(
+            Hyphenation::new(Language::English),
+            "hyphen4tion",
+            vec![Token::Word("hyphen4tion")],
+        );
+    }
+
+    #[test]
+    fn hyphenated_text_is_rendered() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::with_alignment(
+            "a pattern",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 2)),
+            character_style,
+            HorizontalAlignment::Left,
+        )
+        .add_plugin(Hyphenation::new(Language::English))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "....................................",
+            "..........................#.........",
+            "..........................#.........",
+            "..###........###....###..###........",
+            ".#..#........#..#..#..#...#...#####.",
+            ".#..#........#..#..#..#...#.#.......",
+            "..###........###....###....#........",
+            ".............#......................",
+            ".............#......................",
+            "........................            ",
+            "..#.....................            ",
+            "..#.....................            ",
+            ".###....##...#.#...###..            ",
+            "..#....#.##..##.#..#..#.            ",
+            "..#.#..##....#.....#..#.            ",
+            "...#....###..#.....#..#.            ",
+            "........................            ",
+            "........................            ",
+        ]);
+    }
+}
@@ -0,0 +1,396 @@
+//! Inline objects, e.g. icons, in the text flow.
+//!
+//! The [`InlineObjects`] plugin places drawables into the text. Each object replacement character
+//! (`\u{FFFC}`) in the text is replaced by the next object. Objects are laid out like unbreakable
+//! words: an object and the text directly next to it are wrapped together, and lines are made
+//! tall enough to fit their objects.
+
+use embedded_graphics::{
+    draw_target::{DrawTarget, DrawTargetExt},
+    geometry::Dimensions,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::renderer::{CharacterStyle, TextRenderer},
+    Drawable,
+};
+
+use crate::{
+    alignment::InlineAlignment,
+    parser::{Token, OBJECT_REPLACEMENT_CHARACTER},
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+};
+
+/// Inline object plugin.
+///
+/// If the text contains more object replacement characters than there are objects, the remaining
+/// characters are drawn as text.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X10, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::{PrimitiveStyle, Rectangle},
+/// # };
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// use embedded_text::{plugin::inline::InlineObjects, TextBox};
+///
+/// let battery = [Rectangle::new(Point::zero(), Size::new(10, 5))
+///     .into_styled(PrimitiveStyle::with_stroke(BinaryColor::On, 1))];
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 20));
+///
+/// TextBox::new("Battery \u{FFFC} 80%", bounding_box, character_style)
+///     .add_plugin(InlineObjects::new(&battery))
+///     .draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Debug)]
+pub struct InlineObjects<'a, O> {
+    objects: &'a [O],
+    alignment: InlineAlignment,
+
+    /// Index of the object that replaces the next placeholder.
+    next_index: usize,
+
+    /// Rest of a word that was split at a placeholder.
+    pending: Option<&'a str>,
+
+    /// True if the last returned token is not the first piece of a word.
+    continues_word: bool,
+}
+
+impl<'a, O> InlineObjects<'a, O> {
+    /// Returns a new plugin object that places the given objects on the baseline.
+    #[inline]
+    pub const fn new(objects: &'a [O]) -> Self {
+        Self {
+            objects,
+            alignment: InlineAlignment::default(),
+            next_index: 0,
+            pending: None,
+            continues_word: false,
+        }
+    }
+
+    /// Sets the vertical alignment of the objects.
+    #[inline]
+    pub const fn with_alignment(mut self, alignment: InlineAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+}
+
+impl<'a, O> Clone for InlineObjects<'a, O> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            objects: self.objects,
+            alignment: self.alignment,
+            next_index: self.next_index,
+            pending: self.pending,
+            continues_word: self.continues_word,
+        }
+    }
+}
+
+impl<'a, O, C> Plugin<'a, C> for InlineObjects<'a, O>
+where
+    O: Drawable<Color = C> + Dimensions,
+    C: PixelColor,
+{
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        self.continues_word = self.pending.is_some();
+        let word = match self.pending.take() {
+            Some(word) => word,
+            None => match next_token()? {
+                Token::Word(word) => word,
+                token => return Some(token),
+            },
+        };
+
+        match word.find(OBJECT_REPLACEMENT_CHARACTER) {
+            Some(0) => {
+                let (placeholder, rest) = word.split_at(OBJECT_REPLACEMENT_CHARACTER.len());
+                if !rest.is_empty() {
+                    self.pending = Some(rest);
+                }
+
+                match self.objects.get(self.next_index) {
+                    Some(object) => {
+                        let index = self.next_index;
+                        self.next_index += 1;
+
+                        Some(Token::inline_object(
+                            index,
+                            object.bounding_box().size,
+                            self.alignment,
+                        ))
+                    }
+                    None => Some(Token::Word(placeholder)),
+                }
+            }
+            Some(pos) => {
+                let (text, rest) = word.split_at(pos);
+                self.pending = Some(rest);
+
+                Some(Token::Word(text))
+            }
+            None => Some(Token::Word(word)),
+        }
+    }
+
+    #[inline]
+    fn continues_word(&self) -> bool {
+        self.continues_word
+    }
+
+    #[inline]
+    fn draw_inline_object<D>(
+        &mut self,
+        draw_target: &mut D,
+        index: usize,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if let Some(object) = self.objects.get(index) {
+            let offset = bounds.top_left - object.bounding_box().top_left;
+            object.draw(&mut draw_target.translated(offset))?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        _cursor: &mut Cursor,
+        _props: &TextBoxProperties<'_, S>,
+    ) {
+        // The objects are numbered again by the render pass.
+        self.next_index = 0;
+        self.pending = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::{Point, Primitive, Size},
+        primitives::{PrimitiveStyle, Rectangle, Styled},
+        Drawable,
+    };
+
+    use crate::{
+        alignment::InlineAlignment,
+        plugin::inline::InlineObjects,
+        style::{HeightMode, TextBoxStyle},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    fn icon(width: u32, height: u32) -> Styled<Rectangle, PrimitiveStyle<BinaryColor>> {
+        Rectangle::new(Point::new(5, 5), Size::new(width, height))
+            .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+    }
+
+    #[track_caller]
+    fn assert_rendered(
+        plugin: InlineObjects<'_, Styled<Rectangle, PrimitiveStyle<BinaryColor>>>,
+        text: &str,
+        chars: u32,
+        height: u32,
+        pattern: &[&str],
+    ) {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            text,
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, chars, 1)),
+            character_style,
+            TextBoxStyle::with_height_mode(HeightMode::FitToText),
+        )
+        .add_plugin(plugin);
+
+        assert_eq!(text_box.bounds.size.height, height);
+
+        text_box.draw(&mut display).unwrap();
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn object_on_baseline() {
+        let objects = [icon(3, 3)];
+
+        assert_rendered(
+            InlineObjects::new(&objects),
+            "a\u{FFFC}b",
+            5,
+            9,
+            &[
+                "              ",
+                "          #   ",
+                "          #   ",
+                "  ### ### ### ",
+                " #  # ### #  #",
+                " #  # ### #  #",
+                "  ###     ### ",
+            ],
+        );
+    }
+
+    #[test]
+    fn tall_object_extends_line() {
+        let objects = [icon(2, 11)];
+
+        assert_rendered(
+            InlineObjects::new(&objects),
+            "a\u{FFFC}b",
+            5,
+            14,
+            &[
+                "      ##     ",
+                "      ##     ",
+                "      ##     ",
+                "      ##     ",
+                "      ##     ",
+                "      ##     ",
+                "      ## #   ",
+                "      ## #   ",
+                "  ### ## ### ",
+                " #  # ## #  #",
+                " #  # ## #  #",
+                "  ###    ### ",
+            ],
+        );
+    }
+
+    #[test]
+    fn centered_object() {
+        let objects = [icon(2, 11)];
+
+        assert_rendered(
+            InlineObjects::new(&objects).with_alignment(InlineAlignment::Middle),
+            "a\u{FFFC}b",
+            5,
+            11,
+            &[
+                "      ##     ",
+                "      ##     ",
+                "      ## #   ",
+                "      ## #   ",
+                "  ### ## ### ",
+                " #  # ## #  #",
+                " #  # ## #  #",
+                "  ### ## ### ",
+                "      ##     ",
+                "      ##     ",
+                "      ##     ",
+            ],
+        );
+    }
+
+    #[test]
+    fn object_is_wrapped_with_adjacent_text() {
+        let objects = [icon(3, 3)];
+
+        assert_rendered(
+            InlineObjects::new(&objects),
+            "ab cd\u{FFFC}",
+            5,
+            18,
+            &[
+                "               ",
+                "       #       ",
+                "       #       ",
+                "  ###  ###     ",
+                " #  #  #  #    ",
+                " #  #  #  #    ",
+                "  ###  ###     ",
+                "               ",
+                "               ",
+                "               ",
+                "          #    ",
+                "          #    ",
+                "  ###   ### ###",
+                " #     #  # ###",
+                " #     #  # ###",
+                "  ###   ###    ",
+            ],
+        );
+    }
+
+    #[test]
+    fn objects_are_drawn_by_their_plugin() {
+        let mut display = MockDisplay::new();
+
+        let small = [icon(2, 2)];
+        let large = [icon(3, 5)];
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        // The second plugin receives the placeholder the first one has no object for.
+        TextBox::new(
+            "\u{FFFC}a\u{FFFC}",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+        )
+        .add_plugin(InlineObjects::new(&small))
+        .add_plugin(InlineObjects::new(&large))
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "           ",
+            "        ###",
+            "        ###",
+            "    ### ###",
+            "## #  # ###",
+            "## #  # ###",
+            "    ###    ",
+        ]);
+    }
+
+    #[test]
+    fn missing_objects_are_drawn_as_text() {
+        let objects = [icon(3, 3)];
+
+        assert_rendered(
+            InlineObjects::new(&objects),
+            "\u{FFFC}b\u{FFFC}",
+            5,
+            9,
+            &[
+                "           ## ",
+                "    #     #  #",
+                "    #        #",
+                "### ###    ## ",
+                "### #  #   #  ",
+                "### #  #      ",
+                "    ###    #  ",
+            ],
+        );
+    }
+}
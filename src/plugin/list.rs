@@ -0,0 +1,357 @@
+//! Bulleted and numbered lists.
+//!
+//! The [`List`] plugin turns paragraphs that start with a list marker into list items. The marker
+//! is drawn in a gutter at the start of the first line, and the wrapped lines of the item are
+//! aligned with the text after the marker.
+//!
+//! A paragraph is a list item if it starts with one of the following markers, followed by a space:
+//!  - a bullet: `-`, `*` or `•`
+//!  - a number followed by `.` or `)`, e.g. `1.` or `12)`
+//!  - a letter followed by `)`, e.g. `a)`
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::PixelColor,
+    primitives::Rectangle,
+    text::{renderer::TextRenderer, Baseline},
+};
+
+use crate::{parser::Token, plugin::Plugin, style::Indent};
+
+/// Returns whether the word is a list marker.
+fn is_marker(word: &str) -> bool {
+    if is_bullet(word) {
+        return true;
+    }
+
+    let mut chars = word.chars();
+    let suffix = chars.next_back();
+    let body = chars.as_str();
+
+    match suffix {
+        Some('.') | Some(')') if (1..=3).contains(&body.len()) => {
+            body.chars().all(|c| c.is_ascii_digit())
+                || (suffix == Some(')') && body.len() == 1 && body.chars().all(char::is_alphabetic))
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether the word is a bullet marker.
+fn is_bullet(word: &str) -> bool {
+    matches!(word, "-" | "*" | "•")
+}
+
+/// List layout plugin.
+///
+/// The gutter is measured in space characters, so that it has the same width in every line. The
+/// marker must fit into the gutter, otherwise it overlaps the text of the item.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X10, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// use embedded_text::{plugin::list::List, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 60));
+///
+/// TextBox::new(
+///     "Shopping list:\n1. apples and oranges\n2. bread",
+///     bounding_box,
+///     character_style,
+/// )
+/// .add_plugin(List::new())
+/// .draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct List<'a, C> {
+    /// Width of the gutter in space characters.
+    gutter: u16,
+
+    /// Replaces bullet markers, if set.
+    bullet: Option<&'static str>,
+
+    /// True if the next token starts a paragraph.
+    paragraph_start: bool,
+
+    /// True if the current paragraph is a list item.
+    in_item: bool,
+
+    /// True if the current line is the first line of a paragraph.
+    first_line: bool,
+
+    /// Marker to be drawn into the next gutter.
+    marker: Option<&'a str>,
+
+    /// Token that was read while looking for a marker.
+    pending: Option<Token<'a, C>>,
+}
+
+impl<'a, C> List<'a, C> {
+    /// Returns a new plugin object with a gutter of 3 spaces.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            gutter: 3,
+            bullet: None,
+            paragraph_start: true,
+            in_item: false,
+            first_line: true,
+            marker: None,
+            pending: None,
+        }
+    }
+
+    /// Sets the width of the gutter, in space characters.
+    #[inline]
+    pub const fn with_gutter(mut self, gutter: u16) -> Self {
+        self.gutter = gutter;
+        self
+    }
+
+    /// Sets the string that is drawn instead of bullet markers.
+    ///
+    /// This can be used to display a glyph that is hard to type, e.g. `•`, for items that start
+    /// with `-` or `*`.
+    #[inline]
+    pub const fn with_bullet(mut self, bullet: &'static str) -> Self {
+        self.bullet = Some(bullet);
+        self
+    }
+
+    fn gutter(&self) -> Token<'a, C> {
+        Token::MoveCursor {
+            chars: self.gutter as i32,
+            draw_background: true,
+        }
+    }
+}
+
+impl<'a, C> Default for List<'a, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for List<'a, C> {
+    #[inline]
+    fn new_line(&mut self) {
+        self.first_line = self.paragraph_start;
+    }
+
+    #[inline]
+    fn line_indent(&self) -> Indent {
+        if self.in_item && !self.first_line {
+            Indent::Spaces(self.gutter)
+        } else {
+            Indent::Pixels(0)
+        }
+    }
+
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        let token = match self.pending.take() {
+            Some(token) => Some(token),
+            None => next_token(),
+        };
+
+        match token {
+            Some(Token::NewLine) => {
+                self.paragraph_start = true;
+                self.in_item = false;
+            }
+            Some(Token::ChangeTextStyle(_)) => {}
+            Some(Token::Word(word)) if self.paragraph_start && is_marker(word) => {
+                self.paragraph_start = false;
+
+                match next_token() {
+                    Some(Token::Whitespace(_, _)) => {
+                        self.in_item = true;
+                        self.marker = Some(match self.bullet {
+                            Some(bullet) if is_bullet(word) => bullet,
+                            _ => word,
+                        });
+
+                        return Some(self.gutter());
+                    }
+                    token => self.pending = token,
+                }
+            }
+            _ => self.paragraph_start = false,
+        }
+
+        token
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        if text == Some("") {
+            if let Some(marker) = self.marker.take() {
+                character_style.draw_string(marker, bounds.top_left, Baseline::Top, draw_target)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::Point,
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{
+        plugin::list::{is_marker, List},
+        style::TextBoxStyle,
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_rendered(plugin: List<'static, BinaryColor>, text: &str, pattern: &[&str]) {
+        let mut display = MockDisplay::new();
+        // The marker is drawn over the background of the gutter.
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::with_textbox_style(
+            text,
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 6, 3)),
+            character_style,
+            TextBoxStyle::default(),
+        )
+        .add_plugin(plugin)
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(pattern);
+    }
+
+    #[test]
+    fn markers() {
+        for marker in ["-", "*", "•", "1.", "12)", "a)"] {
+            assert!(is_marker(marker), "{}", marker);
+        }
+        for word in ["--", "1", "2024.", "I.", "ab)", "a"] {
+            assert!(!is_marker(word), "{}", word);
+        }
+    }
+
+    #[test]
+    fn wrapped_lines_are_aligned_with_text() {
+        assert_rendered(
+            List::new().with_gutter(2),
+            "- ab cd\nef",
+            &[
+                "........................",
+                "...................#....",
+                "...................#....",
+                "..............###..###..",
+                "#####........#..#..#..#.",
+                ".............#..#..#..#.",
+                "..............###..###..",
+                "........................",
+                "........................",
+                "            ............",
+                "            ..........#.",
+                "            ..........#.",
+                "            ..###...###.",
+                "            .#.....#..#.",
+                "            .#.....#..#.",
+                "            ..###...###.",
+                "            ............",
+                "            ............",
+                "............            ",
+                ".........#..            ",
+                "........#.#.            ",
+                "..##....#...            ",
+                ".#.##..###..            ",
+                ".##.....#...            ",
+                "..###...#...            ",
+                "............            ",
+                "............            ",
+            ],
+        );
+    }
+
+    #[test]
+    fn numbered_items() {
+        assert_rendered(
+            List::new(),
+            "1. a b\n2. cd",
+            &[
+                "....................................",
+                "..#............................#....",
+                ".##............................#....",
+                "..#.................###........###..",
+                "..#................#..#........#..#.",
+                "..#.....##.........#..#........#..#.",
+                ".###....##..........###........###..",
+                "....................................",
+                "....................................",
+                "..............................      ",
+                "..##........................#.      ",
+                ".#..#.......................#.      ",
+                "....#...............###...###.      ",
+                "...#...............#.....#..#.      ",
+                "..#.....##.........#.....#..#.      ",
+                ".####...##..........###...###.      ",
+                "..............................      ",
+                "..............................      ",
+            ],
+        );
+    }
+
+    #[test]
+    fn bullet_is_replaced() {
+        assert_rendered(
+            List::new().with_bullet("o").with_gutter(2),
+            "* ab",
+            &[
+                "........................",
+                "...................#....",
+                "...................#....",
+                "..##..........###..###..",
+                ".#..#........#..#..#..#.",
+                ".#..#........#..#..#..#.",
+                "..##..........###..###..",
+                "........................",
+                "........................",
+            ],
+        );
+    }
+}
@@ -0,0 +1,338 @@
+//! Scroll a line of text horizontally.
+
+use az::SaturatingAs;
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::{PixelColor, Point},
+    primitives::Rectangle,
+    text::{
+        renderer::{CharacterStyle, TextRenderer},
+        Baseline,
+    },
+};
+
+use crate::{
+    plugin::Plugin,
+    rendering::{cursor::Cursor, TextBoxProperties},
+    utils::str_width,
+};
+
+/// Marquee scrolling modes.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum MarqueeMode {
+    /// Scroll the text in one direction. The start of the text follows its end, separated by the
+    /// given string.
+    Wrap(&'static str),
+
+    /// Scroll the text until its end is visible, then scroll it back to the start.
+    Bounce,
+}
+
+/// Marquee (ticker) plugin.
+///
+/// Scrolls text that is wider than the text box horizontally. Every time the text box is drawn,
+/// the text is moved by the configured number of pixels. Text that fits into the text box is not
+/// scrolled.
+///
+/// The plugin is meant to be used with single line text and [`LineBreaking::NoWrap`], so that the
+/// text is not wrapped.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X10, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// use embedded_text::{
+///     plugin::marquee::{Marquee, MarqueeMode},
+///     style::{LineBreaking, TextBoxStyle},
+///     TextBox,
+/// };
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 10));
+///
+/// let text_box = TextBox::with_textbox_style(
+///     "Breaking news: this line is too long to be displayed at once.",
+///     bounding_box,
+///     character_style,
+///     TextBoxStyle::with_line_breaking(LineBreaking::NoWrap),
+/// )
+/// .add_plugin(
+///     Marquee::new(MarqueeMode::Wrap(" +++ "))
+///         .with_speed(2)
+///         .with_pauses(30, 0),
+/// );
+///
+/// // Draw a frame of the animation. Every call moves the text.
+/// text_box.draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+///
+/// [`LineBreaking::NoWrap`]: crate::style::LineBreaking::NoWrap
+#[derive(Copy, Clone, Debug)]
+pub struct Marquee {
+    mode: MarqueeMode,
+    speed: u32,
+    start_pause: u32,
+    end_pause: u32,
+
+    /// The number of pixels the text is currently scrolled by.
+    position: u32,
+
+    /// The number of frames to wait before moving the text again.
+    pause: u32,
+
+    /// True if the text is scrolled back towards its start.
+    backwards: bool,
+
+    /// Distance between the two copies of the text in wrap mode, 0 if the text is not scrolled.
+    period: u32,
+}
+
+impl Marquee {
+    /// Returns a new plugin object that scrolls by one pixel in every frame.
+    #[inline]
+    pub const fn new(mode: MarqueeMode) -> Self {
+        Self {
+            mode,
+            speed: 1,
+            start_pause: 0,
+            end_pause: 0,
+            position: 0,
+            pause: 0,
+            backwards: false,
+            period: 0,
+        }
+    }
+
+    /// Sets the number of pixels the text moves in every frame.
+    #[inline]
+    pub const fn with_speed(mut self, speed: u32) -> Self {
+        self.speed = speed;
+
+        self
+    }
+
+    /// Sets the number of frames the text is stopped when its start or its end is reached.
+    ///
+    /// In [`MarqueeMode::Wrap`] mode the end of the text is followed by its start, so only the
+    /// start pause is used.
+    #[inline]
+    pub const fn with_pauses(mut self, start: u32, end: u32) -> Self {
+        self.start_pause = start;
+        self.end_pause = end;
+        self.pause = start;
+
+        self
+    }
+
+    /// Restarts scrolling from the start of the text.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.pause = self.start_pause;
+        self.backwards = false;
+    }
+
+    /// Moves the text for the next frame.
+    fn step(&mut self, max_scroll: u32, period: u32) {
+        if self.pause > 0 {
+            self.pause -= 1;
+            return;
+        }
+
+        if self.backwards {
+            self.position = self.position.saturating_sub(self.speed);
+            if self.position == 0 {
+                self.pause = self.start_pause;
+                self.backwards = false;
+            }
+            return;
+        }
+
+        let position = self.position + self.speed;
+        match self.mode {
+            MarqueeMode::Wrap(_) if position >= period => {
+                // The second copy of the text is now in place of the first one.
+                self.position = position % period;
+                self.pause = self.start_pause;
+            }
+            MarqueeMode::Bounce if position >= max_scroll => {
+                // Stop when the end of the text is reached.
+                self.position = max_scroll;
+                self.pause = self.end_pause;
+                self.backwards = true;
+            }
+            _ => self.position = position,
+        }
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for Marquee {
+    #[inline]
+    fn on_start_render<S: CharacterStyle + TextRenderer>(
+        &mut self,
+        cursor: &mut Cursor,
+        props: &TextBoxProperties<'_, S>,
+    ) {
+        let box_width = props.text_area.size.width;
+        if props.text_width <= box_width {
+            self.reset();
+            self.period = 0;
+            return;
+        }
+
+        let max_scroll = props.text_width - box_width;
+        let period = match self.mode {
+            MarqueeMode::Wrap(gap) => props.text_width + str_width(props.char_style, gap),
+            MarqueeMode::Bounce => max_scroll,
+        };
+
+        // The text may have been changed since the last frame.
+        self.position = self.position.min(period);
+
+        cursor.x_offset -= self.position.saturating_as::<i32>();
+        self.period = match self.mode {
+            MarqueeMode::Wrap(_) => period,
+            MarqueeMode::Bounce => 0,
+        };
+
+        self.step(max_scroll, period);
+    }
+
+    #[inline]
+    fn post_render<T, D>(
+        &mut self,
+        draw_target: &mut D,
+        character_style: &T,
+        text: Option<&str>,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        T: TextRenderer<Color = C>,
+        D: DrawTarget<Color = C>,
+    {
+        if self.period == 0 {
+            return Ok(());
+        }
+
+        match (text, self.mode) {
+            // Draw the start of the text again after the end of the text and the gap.
+            (Some(text), _) if bounds.size.width > 0 => {
+                let position = bounds.top_left + Point::new(self.period.saturating_as(), 0);
+                if text.chars().all(char::is_whitespace) {
+                    character_style.draw_whitespace(
+                        bounds.size.width,
+                        position,
+                        Baseline::Top,
+                        draw_target,
+                    )?;
+                } else {
+                    character_style.draw_string(text, position, Baseline::Top, draw_target)?;
+                }
+            }
+
+            (None, MarqueeMode::Wrap(gap)) => {
+                character_style.draw_string(gap, bounds.top_left, Baseline::Top, draw_target)?;
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mock_display::MockDisplay,
+        mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
+        pixelcolor::BinaryColor,
+        prelude::Point,
+        primitives::Rectangle,
+        Drawable,
+    };
+
+    use crate::{
+        plugin::marquee::{Marquee, MarqueeMode},
+        style::{LineBreaking, TextBoxStyle},
+        utils::test::size_for,
+        TextBox,
+    };
+
+    #[track_caller]
+    fn assert_frames(text: &str, marquee: Marquee, max_chars: u32, frames: &[&str]) {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), size_for(&FONT_6X9, max_chars, 1));
+        let style = TextBoxStyle::with_line_breaking(LineBreaking::NoWrap);
+
+        let text_box =
+            TextBox::with_textbox_style(text, bounds, character_style, style).add_plugin(marquee);
+
+        for frame in frames {
+            let mut display = MockDisplay::new();
+            text_box.draw(&mut display).unwrap();
+
+            let mut expected = MockDisplay::new();
+            TextBox::with_textbox_style(frame, bounds, character_style, style)
+                .draw(&mut expected)
+                .unwrap();
+
+            display.assert_eq(&expected);
+        }
+    }
+
+    #[test]
+    fn text_that_fits_is_not_scrolled() {
+        assert_frames("ab", Marquee::new(MarqueeMode::Bounce), 4, &["ab", "ab"]);
+    }
+
+    #[test]
+    fn bounce() {
+        assert_frames(
+            "abcdef",
+            Marquee::new(MarqueeMode::Bounce)
+                .with_speed(6)
+                .with_pauses(1, 1),
+            4,
+            &[
+                "abcd", "abcd", "bcde", "cdef", "cdef", "bcde", "abcd", "abcd", "bcde",
+            ],
+        );
+    }
+
+    #[test]
+    fn wrap_around() {
+        assert_frames(
+            "abcdefgh",
+            Marquee::new(MarqueeMode::Wrap(" ")).with_speed(12),
+            4,
+            &["abcd", "cdef", "efgh", "gh a", " abc", "bcde"],
+        );
+    }
+
+    #[test]
+    fn wrap_around_does_not_pause_at_the_end() {
+        assert_frames(
+            "abcdefgh",
+            Marquee::new(MarqueeMode::Wrap(" "))
+                .with_speed(12)
+                .with_pauses(1, 1),
+            4,
+            &[
+                "abcd", "abcd", "cdef", "efgh", "gh a", " abc", "bcde", "bcde",
+            ],
+        );
+    }
+}
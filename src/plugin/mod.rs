@@ -33,6 +33,13 @@ use private::Plugin;
 
 #[cfg(feature = "ansi")]
 pub mod ansi;
+pub mod arabic_shaping;
+#[cfg(feature = "hyphenation")]
+pub mod hyphenation;
+pub mod inline;
+pub mod list;
+pub mod marquee;
+pub mod segmentation;
 pub mod tail;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -149,6 +156,11 @@ where
         self.with_mut(|this| this.plugin.new_line());
     }
 
+    #[inline]
+    pub fn line_indent_width(&self, renderer: &impl TextRenderer) -> u32 {
+        self.with(|this| this.plugin.line_indent_width(renderer))
+    }
+
     pub fn set_state(&self, state: ProcessingState) {
         self.with_mut(|this| this.state = state);
     }
@@ -171,6 +183,12 @@ where
         })
     }
 
+    /// Returns whether the peeked token is a piece of the same word as the preceding token.
+    #[inline]
+    pub fn continues_word(&self) -> bool {
+        self.with(|this| this.plugin.continues_word())
+    }
+
     pub fn consume_peeked_token(&self) {
         self.with_mut(|this| this.peeked_token = None);
     }
@@ -237,4 +255,21 @@ where
                 .post_render(draw_target, character_style, text, bounds)
         })
     }
+
+    #[inline]
+    pub fn draw_inline_object<D>(
+        &self,
+        draw_target: &mut D,
+        plugin: usize,
+        index: usize,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.with_mut(|this| {
+            this.plugin
+                .draw_inline_object_of(draw_target, plugin, index, bounds)
+        })
+    }
 }
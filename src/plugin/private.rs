@@ -11,6 +11,7 @@ use object_chain::{Chain, ChainElement, Link};
 use crate::{
     parser::Token,
     rendering::{cursor::Cursor, TextBoxProperties},
+    style::Indent,
 };
 
 /// Plugin trait.
@@ -29,6 +30,26 @@ where
     #[inline]
     fn new_line(&mut self) {}
 
+    /// Returns the indentation of the current line.
+    ///
+    /// Called after `new_line`, before the tokens of the line are processed. The indentation is
+    /// added to the indentation set in the text box style and to the indentation of the other
+    /// plugins.
+    #[inline]
+    fn line_indent(&self) -> Indent {
+        Indent::default()
+    }
+
+    /// Returns the indentation of the current line in pixels.
+    ///
+    /// This is implemented by the plugin chain, which adds up the indentation of its plugins.
+    /// Plugins should implement `line_indent`.
+    #[doc(hidden)]
+    #[inline]
+    fn line_indent_width(&self, renderer: &impl TextRenderer) -> u32 {
+        self.line_indent().into_pixels(renderer)
+    }
+
     /// Generate the next text token.
     #[inline]
     fn next_token(
@@ -38,6 +59,18 @@ where
         next_token()
     }
 
+    /// Returns true if the last token returned by `next_token` continues the previous word.
+    ///
+    /// Plugins that split a word into multiple [`Token::Word`] or [`Token::InlineObject`] tokens
+    /// should return true for every piece except the first one. A word that is split this way is
+    /// moved to the next line as a whole, instead of being wrapped between its pieces.
+    ///
+    /// Called after `next_token`. By default, every token starts a new word.
+    #[inline]
+    fn continues_word(&self) -> bool {
+        false
+    }
+
     /// Modify the current token immediately before it is rendered.
     ///
     /// This function must return the same token type as the input, otherwise the returned token
@@ -63,6 +96,40 @@ where
         Ok(())
     }
 
+    /// Draws an inline object that was produced by this plugin into the given bounds.
+    ///
+    /// `index` is the index of the [`Token::InlineObject`].
+    #[inline]
+    fn draw_inline_object<D>(
+        &mut self,
+        _draw_target: &mut D,
+        _index: usize,
+        _bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        Ok(())
+    }
+
+    /// Draws an inline object using the plugin at position `plugin` in the plugin chain.
+    ///
+    /// This is implemented by the plugin chain, plugins should implement `draw_inline_object`.
+    #[doc(hidden)]
+    #[inline]
+    fn draw_inline_object_of<D>(
+        &mut self,
+        draw_target: &mut D,
+        _plugin: usize,
+        index: usize,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.draw_inline_object(draw_target, index, bounds)
+    }
+
     /// Called before TextBox rendering is started.
     #[inline]
     fn on_start_render<S: CharacterStyle + TextRenderer>(
@@ -77,6 +144,24 @@ where
     fn on_rendering_finished(&mut self) {}
 }
 
+/// Marks an inline object token that was created by a plugin with the position of the plugin.
+fn claim_inline_object<C: PixelColor>(token: Token<'_, C>, plugin: usize) -> Token<'_, C> {
+    match token {
+        Token::InlineObject {
+            plugin: 0,
+            index,
+            size,
+            alignment,
+        } => Token::InlineObject {
+            plugin,
+            index,
+            size,
+            alignment,
+        },
+        token => token,
+    }
+}
+
 impl<'a, C> Plugin<'a, C> for super::NoPlugin<C> where C: PixelColor {}
 
 impl<'a, C, P> Plugin<'a, C> for Chain<P>
@@ -89,11 +174,23 @@ where
         self.object.new_line();
     }
 
+    #[inline]
+    fn line_indent_width(&self, renderer: &impl TextRenderer) -> u32 {
+        self.object.line_indent_width(renderer)
+    }
+
     fn next_token(
         &mut self,
         next_token: impl FnMut() -> Option<Token<'a, C>>,
     ) -> Option<Token<'a, C>> {
-        self.object.next_token(next_token)
+        self.object
+            .next_token(next_token)
+            .map(|token| claim_inline_object(token, self.len()))
+    }
+
+    #[inline]
+    fn continues_word(&self) -> bool {
+        self.object.continues_word()
     }
 
     fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
@@ -115,6 +212,37 @@ where
             .post_render(draw_target, character_style, text, bounds)
     }
 
+    #[inline]
+    fn draw_inline_object<D>(
+        &mut self,
+        draw_target: &mut D,
+        index: usize,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.object.draw_inline_object(draw_target, index, bounds)
+    }
+
+    #[inline]
+    fn draw_inline_object_of<D>(
+        &mut self,
+        draw_target: &mut D,
+        plugin: usize,
+        index: usize,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if plugin == self.len() {
+            self.object.draw_inline_object(draw_target, index, bounds)
+        } else {
+            Ok(())
+        }
+    }
+
     fn on_start_render<S: CharacterStyle + TextRenderer>(
         &mut self,
         cursor: &mut Cursor,
@@ -140,13 +268,26 @@ where
         self.object.new_line();
     }
 
+    #[inline]
+    fn line_indent_width(&self, renderer: &impl TextRenderer) -> u32 {
+        self.parent.line_indent_width(renderer) + self.object.line_indent_width(renderer)
+    }
+
     fn next_token(
         &mut self,
         mut next_token: impl FnMut() -> Option<Token<'a, C>>,
     ) -> Option<Token<'a, C>> {
+        let plugin = self.len();
         let parent = &mut self.parent;
         let next_token = || parent.next_token(&mut next_token);
-        self.object.next_token(next_token)
+        self.object
+            .next_token(next_token)
+            .map(|token| claim_inline_object(token, plugin))
+    }
+
+    #[inline]
+    fn continues_word(&self) -> bool {
+        self.object.continues_word() || self.parent.continues_word()
     }
 
     fn render_token(&mut self, token: Token<'a, C>) -> Option<Token<'a, C>> {
@@ -172,6 +313,38 @@ where
             .post_render(draw_target, character_style, text, bounds)
     }
 
+    #[inline]
+    fn draw_inline_object<D>(
+        &mut self,
+        draw_target: &mut D,
+        index: usize,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        self.object.draw_inline_object(draw_target, index, bounds)
+    }
+
+    #[inline]
+    fn draw_inline_object_of<D>(
+        &mut self,
+        draw_target: &mut D,
+        plugin: usize,
+        index: usize,
+        bounds: Rectangle,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if plugin == self.len() {
+            self.object.draw_inline_object(draw_target, index, bounds)
+        } else {
+            self.parent
+                .draw_inline_object_of(draw_target, plugin, index, bounds)
+        }
+    }
+
     fn on_start_render<S: CharacterStyle + TextRenderer>(
         &mut self,
         cursor: &mut Cursor,
@@ -186,3 +359,37 @@ where
         self.object.on_rendering_finished();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mono_font::{ascii::FONT_6X9, MonoTextStyle},
+        pixelcolor::BinaryColor,
+    };
+    use object_chain::{Chain, ChainElement};
+
+    use crate::{plugin::private::Plugin, style::Indent};
+
+    #[derive(Clone)]
+    struct Indented(Indent);
+
+    impl<'a> Plugin<'a, BinaryColor> for Indented {
+        fn line_indent(&self) -> Indent {
+            self.0
+        }
+    }
+
+    #[test]
+    fn line_indents_of_chained_plugins_are_added() {
+        let renderer = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+
+        let plugins = Chain::new(Indented(Indent::Pixels(2)))
+            .append(Indented(Indent::Spaces(1)))
+            .append(Indented(Indent::Pixels(0)));
+
+        assert_eq!(
+            Plugin::<BinaryColor>::line_indent_width(&plugins, &renderer),
+            8
+        );
+    }
+}
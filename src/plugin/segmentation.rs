@@ -0,0 +1,327 @@
+//! Dictionary based word segmentation.
+//!
+//! Thai, Lao, Khmer and Myanmar text is written without spaces between words, and the line
+//! breaking rules of these scripts can't be described by character classes. The
+//! [`WordSegmentation`] plugin splits words of these scripts using a dictionary and inserts line
+//! break opportunities between them. Text that can't be segmented is kept together.
+//!
+//! The dictionary is a list of words, one per line, sorted in byte order. It is stored as a single
+//! static string, so it can be placed in flash memory, e.g. using `include_str!`:
+//!
+//! ```rust
+//! use embedded_text::plugin::segmentation::Dictionary;
+//!
+//! // In a real application, the list would be loaded using
+//! // `Dictionary::new(include_str!("thai-words.txt"))`.
+//! const THAI: Dictionary = Dictionary::new("กิน\nข้าว\nฉัน\n");
+//! ```
+
+use embedded_graphics::prelude::PixelColor;
+
+use crate::{parser::Token, plugin::Plugin};
+
+/// Returns whether the character belongs to a script that needs to be segmented.
+fn needs_segmentation(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}'   // Thai, Lao
+        | '\u{1000}'..='\u{109F}' // Myanmar
+        | '\u{1780}'..='\u{17FF}' // Khmer
+        | '\u{19E0}'..='\u{19FF}' // Khmer symbols
+    )
+}
+
+/// Returns whether the character can't start a word, e.g. a vowel sign or a tone mark.
+fn is_mark(c: char) -> bool {
+    matches!(c,
+        '\u{0E31}' | '\u{0E34}'..='\u{0E3A}' | '\u{0E47}'..='\u{0E4E}' // Thai
+        | '\u{0EB1}' | '\u{0EB4}'..='\u{0EBC}' | '\u{0EC8}'..='\u{0ECE}' // Lao
+        | '\u{102B}'..='\u{103E}' | '\u{1056}'..='\u{1059}' // Myanmar
+        | '\u{17B4}'..='\u{17D3}' | '\u{17DD}' // Khmer
+    )
+}
+
+/// Word list used by the [`WordSegmentation`] plugin.
+///
+/// The words are stored in a single string, one word per line (separated by `\n` or `\r\n`),
+/// sorted in byte order. Lookups use binary search over the lines of the string, so no additional
+/// memory is needed. The word list is not compressed, a word list that shares prefixes (like a
+/// trie) would need to be generated at build time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dictionary {
+    words: &'static str,
+}
+
+impl Dictionary {
+    /// Creates a dictionary from a sorted, newline separated list of words.
+    ///
+    /// The words must be sorted in byte order (e.g. using `LC_ALL=C sort`), otherwise lookups
+    /// may fail. Unsorted words are detected by debug assertions when they are looked up.
+    #[inline]
+    pub const fn new(words: &'static str) -> Self {
+        Self { words }
+    }
+
+    /// Returns the word in the line that starts at `start`, and the end of the line.
+    fn word_at(&self, start: usize) -> (&'static str, usize) {
+        let words = self.words;
+        let end = words.as_bytes()[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(words.len(), |idx| start + idx);
+        let word = &words[start..end];
+
+        (word.strip_suffix('\r').unwrap_or(word), end)
+    }
+
+    /// Returns whether the word in the line that starts at `start` is not less than the word in
+    /// the previous line.
+    fn is_sorted_at(&self, start: usize) -> bool {
+        if start == 0 {
+            return true;
+        }
+
+        let prev_start = self.words.as_bytes()[..start - 1]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |idx| idx + 1);
+
+        self.word_at(prev_start).0 <= self.word_at(start).0
+    }
+
+    /// Returns the first word that is not less than `key`.
+    fn lower_bound(&self, key: &str) -> &'static str {
+        let words = self.words;
+        let bytes = words.as_bytes();
+
+        // `lo` and `hi` are always at the start of a line.
+        let mut lo = 0;
+        let mut hi = words.len();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let start = bytes[lo..mid]
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(lo, |idx| lo + idx + 1);
+            let (word, end) = self.word_at(start);
+
+            debug_assert!(self.is_sorted_at(start), "Dictionary words are not sorted");
+
+            if word < key {
+                lo = (end + 1).min(words.len());
+            } else {
+                hi = start;
+            }
+        }
+
+        self.word_at(lo).0
+    }
+
+    /// Returns the lengths of the dictionary words the text starts with, shortest first.
+    fn prefixes<'t>(&'t self, text: &'t str) -> impl Iterator<Item = usize> + 't {
+        text.char_indices()
+            .map(|(idx, c)| idx + c.len_utf8())
+            .map(move |len| (len, self.lower_bound(&text[..len])))
+            // No longer words start with this prefix.
+            .take_while(move |&(len, word)| word.starts_with(&text[..len]))
+            .filter(|&(len, word)| word.len() == len)
+            .map(|(len, _)| len)
+    }
+
+    /// Returns whether a dictionary word starts at the beginning of the text.
+    fn starts_word(&self, text: &str) -> bool {
+        !text.starts_with(is_mark) && self.prefixes(text).next().is_some()
+    }
+}
+
+/// Word segmentation plugin.
+///
+/// Words are found using maximal matching: the longest dictionary word is used, unless a shorter
+/// one is followed by another dictionary word and the longer one isn't. Unknown text is kept
+/// together until the next dictionary word.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X10, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// use embedded_text::{
+///     plugin::segmentation::{Dictionary, WordSegmentation},
+///     TextBox,
+/// };
+///
+/// // In a real application, the list would be loaded using `include_str!`.
+/// const THAI: Dictionary = Dictionary::new("กิน\nข้าว\nฉัน\n");
+///
+/// // A font that contains Thai characters should be used here.
+/// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 20));
+///
+/// TextBox::new("ฉันกินข้าว", bounding_box, character_style)
+///     .add_plugin(WordSegmentation::new(THAI))
+///     .draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct WordSegmentation<'a> {
+    dictionary: Dictionary,
+
+    /// The unprocessed part of the current word.
+    word: &'a str,
+
+    /// True if the previous token ended at a word boundary.
+    pending_break: bool,
+}
+
+impl<'a> WordSegmentation<'a> {
+    /// Returns a new plugin object that uses the given dictionary.
+    #[inline]
+    pub const fn new(dictionary: Dictionary) -> Self {
+        Self {
+            dictionary,
+            word: "",
+            pending_break: false,
+        }
+    }
+
+    /// Returns the length of the first segment of the text.
+    fn next_segment(&self, text: &str) -> usize {
+        let mut longest = None;
+        for len in self.dictionary.prefixes(text) {
+            let rest = &text[len..];
+            if rest.starts_with(is_mark) {
+                // The match ends inside a syllable.
+                continue;
+            }
+            if rest.is_empty() || self.dictionary.starts_word(rest) {
+                longest = Some((len, true));
+            } else if !matches!(longest, Some((_, true))) {
+                longest = Some((len, false));
+            }
+        }
+
+        if let Some((len, _)) = longest {
+            return len;
+        }
+
+        // Keep unknown text together until the next dictionary word.
+        text.char_indices()
+            .skip(1)
+            .find(|&(idx, _)| self.dictionary.starts_word(&text[idx..]))
+            .map_or(text.len(), |(idx, _)| idx)
+    }
+}
+
+impl<'a, C: PixelColor> Plugin<'a, C> for WordSegmentation<'a> {
+    #[inline]
+    fn next_token(
+        &mut self,
+        mut next_token: impl FnMut() -> Option<Token<'a, C>>,
+    ) -> Option<Token<'a, C>> {
+        if self.pending_break {
+            self.pending_break = false;
+            return Some(Token::Break(""));
+        }
+
+        if self.word.is_empty() {
+            match next_token() {
+                Some(Token::Word(word)) if word.contains(needs_segmentation) => {
+                    self.word = word;
+                }
+                token => return token,
+            }
+        }
+
+        let (segment, rest) = self.word.split_at(self.next_segment(self.word));
+        self.word = rest;
+        self.pending_break = !rest.is_empty();
+
+        Some(Token::Word(segment))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::pixelcolor::BinaryColor;
+
+    use crate::{
+        parser::{Parser, Token},
+        plugin::{
+            segmentation::{Dictionary, WordSegmentation},
+            Plugin,
+        },
+    };
+
+    #[track_caller]
+    fn assert_segments(dictionary: &'static str, text: &str, segments: &[&str]) {
+        let mut plugin = WordSegmentation::new(Dictionary::new(dictionary));
+        let mut parser = Parser::parse(text);
+
+        let mut expected = vec![];
+        for segment in segments {
+            if !expected.is_empty() {
+                expected.push(Token::Break(""));
+            }
+            expected.push(Token::Word(segment));
+        }
+
+        let result = core::iter::from_fn(|| plugin.next_token(|| parser.next()))
+            .collect::<Vec<Token<BinaryColor>>>();
+
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn dictionary_lookup() {
+        let dictionary = Dictionary::new("a\nab\nabc\nb\nbcd\nd\n");
+
+        assert_eq!(dictionary.prefixes("abcd").collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(dictionary.prefixes("bcde").collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(dictionary.prefixes("c").count(), 0);
+        assert_eq!(dictionary.prefixes("e").count(), 0);
+    }
+
+    #[test]
+    fn dictionary_with_crlf_line_endings() {
+        let dictionary = Dictionary::new("a\r\nab\r\nabc\r\nb\r\nbcd\r\nd\r\n");
+
+        assert_eq!(dictionary.prefixes("abcd").collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(dictionary.prefixes("bcde").collect::<Vec<_>>(), [1, 3]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Dictionary words are not sorted")]
+    fn unsorted_dictionary_is_detected() {
+        let dictionary = Dictionary::new("b\na\nc\n");
+
+        dictionary.prefixes("c").count();
+    }
+
+    #[test]
+    fn segments_words() {
+        assert_segments("กิน\nข้าว\nฉัน\n", "ฉันกินข้าว", &["ฉัน", "กิน", "ข้าว"]);
+    }
+
+    #[test]
+    fn prefers_segmentation_that_continues_with_known_word() {
+        // "มาก" is the longest match, but it would leave "ว่า", which is not in the dictionary.
+        assert_segments("กว่า\nมา\nมาก\n", "มากว่า", &["มา", "กว่า"]);
+        assert_segments("กว่า\nมา\nมาก\n", "มากมา", &["มาก", "มา"]);
+    }
+
+    #[test]
+    fn unknown_text_is_kept_together() {
+        assert_segments("กิน\n", "ฉันกินอะไร", &["ฉัน", "กิน", "อะไร"]);
+    }
+
+    #[test]
+    fn other_words_are_not_segmented() {
+        assert_segments("a\nb\n", "ab", &["ab"]);
+    }
+}
@@ -24,7 +24,7 @@ impl<'a, C: PixelColor> Plugin<'a, C> for Tail {
         cursor: &mut Cursor,
         props: &TextBoxProperties<'_, S>,
     ) {
-        let box_height = props.bounding_box.size.height.saturating_as();
+        let box_height = props.text_area.size.height.saturating_as();
         if props.text_height > box_height {
             let offset = box_height - props.text_height;
 
@@ -0,0 +1,921 @@
+//! Bidirectional text layout.
+//!
+//! This module implements the parts of the Unicode Bidirectional Algorithm (UAX #9) that don't
+//! need explicit embedding levels. Lines are laid out in logical order first, collecting runs of
+//! text with the same embedding level and their horizontal position. The runs are then reordered
+//! and rendering maps every piece of text to its visual position.
+
+use core::cmp::Ordering;
+
+use embedded_graphics::{
+    prelude::{Point, Size},
+    text::renderer::{CharacterStyle, TextRenderer},
+};
+
+use crate::{
+    alignment::InlineAlignment,
+    parser::{ChangeTextStyle, OBJECT_REPLACEMENT_CHARACTER},
+    rendering::{fonts::SelectFont, line_iter::ElementHandler, space_config::GlyphSpacing},
+    style::TextDirection,
+    utils::{graphemes, str_width, str_width_and_left_offset},
+};
+
+/// The maximum number of level runs in a line.
+///
+/// Text after the last run is displayed as part of the last run.
+const MAX_RUNS: usize = 16;
+
+/// Bidirectional character types.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[allow(clippy::upper_case_acronyms)]
+enum BidiClass {
+    /// Left-to-right
+    L,
+    /// Right-to-left
+    R,
+    /// Right-to-left Arabic
+    AL,
+    /// European number
+    EN,
+    /// European number separator
+    ES,
+    /// European number terminator
+    ET,
+    /// Arabic number
+    AN,
+    /// Common number separator
+    CS,
+    /// Nonspacing mark
+    NSM,
+    /// Boundary neutral
+    BN,
+    /// Paragraph separator
+    B,
+    /// Segment separator
+    S,
+    /// Whitespace
+    WS,
+    /// Other neutrals
+    ON,
+}
+
+use BidiClass::*;
+
+/// Bidirectional character types, except for the default `L` type.
+///
+/// This is a simplified version of the Unicode Character Database that covers the Hebrew, Arabic,
+/// Syriac, Thaana, NKo and related scripts, common punctuation and symbols.
+const CLASSES: &[(u32, u32, BidiClass)] = &[
+    (0x0000, 0x0008, BN),
+    (0x0009, 0x0009, S),
+    (0x000A, 0x000A, B),
+    (0x000B, 0x000B, S),
+    (0x000C, 0x000C, WS),
+    (0x000D, 0x000D, B),
+    (0x000E, 0x001B, BN),
+    (0x001C, 0x001E, B),
+    (0x001F, 0x001F, S),
+    (0x0020, 0x0020, WS),
+    (0x0021, 0x0022, ON),
+    (0x0023, 0x0025, ET),
+    (0x0026, 0x002A, ON),
+    (0x002B, 0x002B, ES),
+    (0x002C, 0x002C, CS),
+    (0x002D, 0x002D, ES),
+    (0x002E, 0x002F, CS),
+    (0x0030, 0x0039, EN),
+    (0x003A, 0x003A, CS),
+    (0x003B, 0x0040, ON),
+    (0x005B, 0x0060, ON),
+    (0x007B, 0x007E, ON),
+    (0x007F, 0x0084, BN),
+    (0x0085, 0x0085, B),
+    (0x0086, 0x009F, BN),
+    (0x00A0, 0x00A0, CS),
+    (0x00A1, 0x00A1, ON),
+    (0x00A2, 0x00A5, ET),
+    (0x00A6, 0x00A9, ON),
+    (0x00AB, 0x00AC, ON),
+    (0x00AD, 0x00AD, BN),
+    (0x00AE, 0x00AF, ON),
+    (0x00B0, 0x00B1, ET),
+    (0x00B2, 0x00B3, EN),
+    (0x00B4, 0x00B4, ON),
+    (0x00B6, 0x00B8, ON),
+    (0x00B9, 0x00B9, EN),
+    (0x00BB, 0x00BF, ON),
+    (0x00D7, 0x00D7, ON),
+    (0x00F7, 0x00F7, ON),
+    (0x0300, 0x036F, NSM),
+    (0x0590, 0x0590, R),
+    (0x0591, 0x05BD, NSM),
+    (0x05BE, 0x05BE, R),
+    (0x05BF, 0x05BF, NSM),
+    (0x05C0, 0x05C0, R),
+    (0x05C1, 0x05C2, NSM),
+    (0x05C3, 0x05C3, R),
+    (0x05C4, 0x05C5, NSM),
+    (0x05C6, 0x05C6, R),
+    (0x05C7, 0x05C7, NSM),
+    (0x05C8, 0x05FF, R),
+    (0x0600, 0x0605, AN),
+    (0x0606, 0x0607, ON),
+    (0x0608, 0x0608, AL),
+    (0x0609, 0x060A, ET),
+    (0x060B, 0x060B, AL),
+    (0x060C, 0x060C, CS),
+    (0x060D, 0x060D, AL),
+    (0x060E, 0x060F, ON),
+    (0x0610, 0x061A, NSM),
+    (0x061B, 0x064A, AL),
+    (0x064B, 0x065F, NSM),
+    (0x0660, 0x0669, AN),
+    (0x066A, 0x066A, ET),
+    (0x066B, 0x066C, AN),
+    (0x066D, 0x066F, AL),
+    (0x0670, 0x0670, NSM),
+    (0x0671, 0x06D5, AL),
+    (0x06D6, 0x06DC, NSM),
+    (0x06DD, 0x06DD, AN),
+    (0x06DE, 0x06DE, ON),
+    (0x06DF, 0x06E4, NSM),
+    (0x06E5, 0x06E6, AL),
+    (0x06E7, 0x06E8, NSM),
+    (0x06E9, 0x06E9, ON),
+    (0x06EA, 0x06ED, NSM),
+    (0x06EE, 0x06EF, AL),
+    (0x06F0, 0x06F9, EN),
+    (0x06FA, 0x0710, AL),
+    (0x0711, 0x0711, NSM),
+    (0x0712, 0x072F, AL),
+    (0x0730, 0x074A, NSM),
+    (0x074B, 0x07A5, AL),
+    (0x07A6, 0x07B0, NSM),
+    (0x07B1, 0x07BF, AL),
+    (0x07C0, 0x07EA, R),
+    (0x07EB, 0x07F3, NSM),
+    (0x07F4, 0x07FF, R),
+    (0x0800, 0x0815, R),
+    (0x0816, 0x082D, NSM),
+    (0x082E, 0x0858, R),
+    (0x0859, 0x085B, NSM),
+    (0x085C, 0x085F, R),
+    (0x0860, 0x0897, AL),
+    (0x0898, 0x089F, NSM),
+    (0x08A0, 0x08C9, AL),
+    (0x08CA, 0x08E1, NSM),
+    (0x08E2, 0x08E2, AN),
+    (0x08E3, 0x08FF, NSM),
+    (0x2000, 0x200A, WS),
+    (0x200B, 0x200D, BN),
+    (0x200F, 0x200F, R),
+    (0x2010, 0x2027, ON),
+    (0x2028, 0x2028, WS),
+    (0x2029, 0x2029, B),
+    (0x202A, 0x202E, BN),
+    (0x202F, 0x202F, CS),
+    (0x2030, 0x2034, ET),
+    (0x2035, 0x2043, ON),
+    (0x2044, 0x2044, CS),
+    (0x2045, 0x205E, ON),
+    (0x205F, 0x205F, WS),
+    (0x2060, 0x206F, BN),
+    (0x2070, 0x2070, EN),
+    (0x2074, 0x2079, EN),
+    (0x207A, 0x207B, ES),
+    (0x207C, 0x207E, ON),
+    (0x2080, 0x2089, EN),
+    (0x208A, 0x208B, ES),
+    (0x208C, 0x208E, ON),
+    (0x20A0, 0x20CF, ET),
+    (0x2190, 0x2211, ON),
+    (0x2212, 0x2212, ES),
+    (0x2213, 0x2213, ET),
+    (0x2214, 0x2335, ON),
+    (0x2500, 0x27FF, ON),
+    (0x2900, 0x2BFF, ON),
+    (0x3000, 0x3000, WS),
+    (0x3001, 0x3004, ON),
+    (0xFB1D, 0xFB1D, R),
+    (0xFB1E, 0xFB1E, NSM),
+    (0xFB1F, 0xFB28, R),
+    (0xFB29, 0xFB29, ES),
+    (0xFB2A, 0xFB4F, R),
+    (0xFB50, 0xFD3D, AL),
+    (0xFD3E, 0xFD3F, ON),
+    (0xFD40, 0xFDFC, AL),
+    (0xFDFD, 0xFDFD, ON),
+    (0xFDFE, 0xFDFF, AL),
+    (0xFE00, 0xFE0F, NSM),
+    (0xFE20, 0xFE2F, NSM),
+    (0xFE50, 0xFE50, CS),
+    (0xFE51, 0xFE51, ON),
+    (0xFE52, 0xFE52, CS),
+    (0xFE54, 0xFE54, ON),
+    (0xFE55, 0xFE55, CS),
+    (0xFE56, 0xFE5E, ON),
+    (0xFE5F, 0xFE5F, ET),
+    (0xFE60, 0xFE61, ON),
+    (0xFE62, 0xFE63, ES),
+    (0xFE64, 0xFE68, ON),
+    (0xFE69, 0xFE6A, ET),
+    (0xFE6B, 0xFE6B, ON),
+    (0xFE70, 0xFEFE, AL),
+    (0xFEFF, 0xFEFF, BN),
+    (0xFF01, 0xFF02, ON),
+    (0xFF03, 0xFF05, ET),
+    (0xFF06, 0xFF0A, ON),
+    (0xFF0B, 0xFF0B, ES),
+    (0xFF0C, 0xFF0C, CS),
+    (0xFF0D, 0xFF0D, ES),
+    (0xFF0E, 0xFF0F, CS),
+    (0xFF10, 0xFF19, EN),
+    (0xFF1A, 0xFF1A, CS),
+    (0xFF1B, 0xFF20, ON),
+    (0xFF3B, 0xFF40, ON),
+    (0xFF5B, 0xFF65, ON),
+    (0xFFE0, 0xFFE1, ET),
+    (0xFFE5, 0xFFE6, ET),
+    (0x10800, 0x10CFF, R),
+    (0x10D00, 0x10D23, AL),
+    (0x10D24, 0x10D27, NSM),
+    (0x10D30, 0x10D39, AN),
+    (0x10D3A, 0x10E5F, R),
+    (0x10E60, 0x10E7E, AN),
+    (0x10E7F, 0x10F2F, R),
+    (0x10F30, 0x10F45, AL),
+    (0x10F46, 0x10F50, NSM),
+    (0x10F51, 0x10FFF, R),
+    (0x1E800, 0x1EDFF, R),
+    (0x1EE00, 0x1EEFF, AL),
+    (0x1EF00, 0x1EFFF, R),
+    (0x1F100, 0x1F10A, EN),
+];
+
+fn bidi_class(c: char) -> BidiClass {
+    let c = c as u32;
+    match CLASSES.binary_search_by(|&(start, end, _)| {
+        if end < c {
+            Ordering::Less
+        } else if start > c {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }) {
+        Ok(idx) => CLASSES[idx].2,
+        Err(_) => L,
+    }
+}
+
+/// Splits a string into the pieces of text that are reordered as a whole.
+///
+/// Nonspacing marks stay attached to their base character, even if the `grapheme` feature is
+/// disabled.
+fn clusters(st: &str) -> impl Iterator<Item = &str> {
+    let mut rest = st;
+
+    core::iter::from_fn(move || {
+        let mut graphemes = graphemes(rest);
+        let (_, first) = graphemes.next()?;
+        let len = graphemes
+            .take_while(|(_, grapheme)| grapheme.chars().next().map(bidi_class) == Some(NSM))
+            .last()
+            .map_or(first.len(), |(idx, grapheme)| idx + grapheme.len());
+
+        let (cluster, tail) = rest.split_at(len);
+        rest = tail;
+
+        Some(cluster)
+    })
+}
+
+/// Splits a string into the pieces of text that are reordered as a whole, and their widths.
+///
+/// The width of the last piece is adjusted so that the sum of the widths is `width`.
+fn cluster_widths<'s, S: TextRenderer>(
+    renderer: &'s S,
+    st: &'s str,
+    width: u32,
+) -> impl Iterator<Item = (&'s str, u32)> + 's {
+    let mut remaining = width;
+    let mut iter = clusters(st).peekable();
+
+    core::iter::from_fn(move || {
+        let cluster = iter.next()?;
+        let width = if iter.peek().is_some() {
+            str_width(renderer, cluster).min(remaining)
+        } else {
+            remaining
+        };
+        remaining -= width;
+
+        Some((cluster, width))
+    })
+}
+
+/// Returns the glyph that is displayed for a character in right-to-left text.
+fn mirrored(c: char) -> char {
+    match c {
+        '(' => ')',
+        ')' => '(',
+        '<' => '>',
+        '>' => '<',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        c => c,
+    }
+}
+
+/// Direction used to resolve neutral characters.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Direction {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Direction {
+    const fn from_rtl(rtl: bool) -> Self {
+        if rtl {
+            Direction::RightToLeft
+        } else {
+            Direction::LeftToRight
+        }
+    }
+}
+
+/// Bidi state that is carried between the lines of a paragraph.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ParagraphState {
+    /// The paragraph is right-to-left.
+    pub rtl: bool,
+
+    /// The paragraph contains right-to-left text.
+    contains_rtl: bool,
+
+    /// The last strong character type (`L`, `R` or `AL`).
+    last_strong: BidiClass,
+
+    /// The direction of the last strong character or number.
+    last_direction: Direction,
+}
+
+impl ParagraphState {
+    pub const fn new() -> Self {
+        Self {
+            rtl: false,
+            contains_rtl: false,
+            last_strong: L,
+            last_direction: Direction::LeftToRight,
+        }
+    }
+
+    /// Resolves the direction of a new paragraph from its words.
+    pub fn start<'a>(direction: TextDirection, words: impl Iterator<Item = &'a str>) -> Self {
+        let mut first_strong = None;
+        let mut contains_rtl = false;
+
+        for c in words.flat_map(str::chars) {
+            let class = bidi_class(c);
+            if first_strong.is_none() && matches!(class, L | R | AL) {
+                first_strong = Some(class);
+            }
+            if matches!(class, R | AL | AN) {
+                contains_rtl = true;
+            }
+            if contains_rtl && first_strong.is_some() {
+                break;
+            }
+        }
+
+        let rtl = match direction {
+            TextDirection::LeftToRight => false,
+            TextDirection::RightToLeft => true,
+            TextDirection::Auto => matches!(first_strong, Some(R) | Some(AL)),
+        };
+
+        Self {
+            rtl,
+            contains_rtl,
+            last_strong: if rtl { R } else { L },
+            last_direction: Direction::from_rtl(rtl),
+        }
+    }
+
+    /// Returns whether the lines of the paragraph need to be reordered.
+    pub const fn needs_layout(&self) -> bool {
+        self.rtl || self.contains_rtl
+    }
+
+    const fn level(&self, direction: Direction) -> u8 {
+        match (direction, self.rtl) {
+            (Direction::LeftToRight, false) => 0,
+            (Direction::RightToLeft, _) => 1,
+            (Direction::LeftToRight, true) => 2,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Run {
+    /// Logical position of the run.
+    start: i32,
+    width: u32,
+    level: u8,
+
+    /// Visual position of the run.
+    visual_start: i32,
+}
+
+/// Neutral characters that precede the current character.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum Neutrals {
+    None,
+
+    /// A single separator character after a number (W4).
+    Separator(BidiClass, BidiClass),
+
+    /// European terminators (W5).
+    Terminators,
+
+    Other,
+}
+
+/// Level runs of a single line.
+#[derive(Clone, Debug)]
+pub(crate) struct LineLayout {
+    state: ParagraphState,
+    runs: [Run; MAX_RUNS],
+    run_count: usize,
+
+    /// Logical position of the next character.
+    x: i32,
+
+    /// Start and width of the neutral characters whose level is not known yet.
+    neutral_start: i32,
+    neutral_width: u32,
+    neutrals: Neutrals,
+
+    /// The type of the previous character, after resolving weak types.
+    prev: BidiClass,
+
+    /// The line contains cursor movements that can't be reordered.
+    disabled: bool,
+}
+
+impl LineLayout {
+    pub fn new(state: ParagraphState, x: i32) -> Self {
+        Self {
+            state,
+            runs: [Run::default(); MAX_RUNS],
+            run_count: 0,
+            x,
+            neutral_start: x,
+            neutral_width: 0,
+            neutrals: Neutrals::None,
+            prev: if state.rtl { R } else { L },
+            disabled: false,
+        }
+    }
+
+    /// Returns the state that should be used to lay out the next line of the paragraph.
+    pub fn paragraph_state(&self) -> ParagraphState {
+        self.state
+    }
+
+    fn push_run(&mut self, start: i32, width: u32, level: u8) {
+        if width == 0 {
+            return;
+        }
+
+        match self.runs[..self.run_count].last_mut() {
+            Some(last) if last.level == level || self.run_count == MAX_RUNS => {
+                last.width += width;
+            }
+            _ => {
+                self.runs[self.run_count] = Run {
+                    start,
+                    width,
+                    level,
+                    visual_start: start,
+                };
+                self.run_count += 1;
+            }
+        }
+    }
+
+    /// Adds neutral characters of the given width.
+    fn push_neutral(&mut self, class: BidiClass, width: u32) {
+        if self.neutral_width == 0 {
+            self.neutral_start = self.x;
+        }
+
+        self.neutrals = match (self.neutrals, class) {
+            // W5: European terminators after a European number are part of the number.
+            (Neutrals::None, ET) if self.prev == EN => {
+                let class = if self.state.last_strong == L { L } else { EN };
+                self.push_strong(class, EN, width);
+                return;
+            }
+            (Neutrals::None, ES | CS) if matches!(self.prev, EN | AN) => {
+                Neutrals::Separator(class, self.prev)
+            }
+            (Neutrals::None | Neutrals::Terminators, ET) => Neutrals::Terminators,
+            _ => Neutrals::Other,
+        };
+
+        self.neutral_width += width;
+        self.x += width as i32;
+        self.prev = class;
+    }
+
+    /// Adds a strong character or a number.
+    ///
+    /// `class` is the type of the character after resolving weak types, `number` is the type
+    /// before applying rule W7.
+    fn push_strong(&mut self, class: BidiClass, number: BidiClass, width: u32) {
+        let direction = match class {
+            L => Direction::LeftToRight,
+            _ => Direction::RightToLeft,
+        };
+        // I1, I2: numbers are always displayed left-to-right.
+        let level = match class {
+            L => self.state.level(Direction::LeftToRight),
+            R => 1,
+            _ => 2,
+        };
+
+        let neutral_level = match self.neutrals {
+            // W4: a single separator between two numbers of the same type.
+            Neutrals::Separator(separator, before)
+                if number == before && (number == EN || separator == CS) =>
+            {
+                level
+            }
+            // W5: European terminators before a European number.
+            Neutrals::Terminators if number == EN => level,
+            // N1: neutrals between characters of the same direction take that direction.
+            _ if self.state.last_direction == direction => self.state.level(direction),
+            // N2: other neutrals take the paragraph direction.
+            _ => self.state.rtl as u8,
+        };
+
+        self.push_run(self.neutral_start, self.neutral_width, neutral_level);
+        self.neutral_width = 0;
+        self.neutrals = Neutrals::None;
+
+        self.push_run(self.x, width, level);
+        self.x += width as i32;
+
+        self.state.last_direction = direction;
+        self.prev = number;
+    }
+
+    /// Adds a character and the nonspacing marks that follow it.
+    fn push_cluster(&mut self, cluster: &str, width: u32) {
+        let class = cluster.chars().next().map_or(BN, bidi_class);
+
+        let class = match class {
+            // W1: nonspacing marks take the type of the previous character.
+            NSM => self.prev,
+            class => class,
+        };
+
+        match class {
+            L => {
+                self.state.last_strong = L;
+                self.push_strong(L, L, width);
+            }
+            R | AL => {
+                self.state.last_strong = class;
+                self.push_strong(R, R, width);
+            }
+            // W2: European numbers after Arabic letters are Arabic numbers.
+            EN if self.state.last_strong == AL => self.push_strong(AN, AN, width),
+            // W7: European numbers after left-to-right text are left-to-right.
+            EN if self.state.last_strong == L => self.push_strong(L, EN, width),
+            EN | AN => self.push_strong(class, class, width),
+            _ => self.push_neutral(class, width),
+        }
+    }
+
+    /// Resolves the remaining characters and calculates the visual position of the runs.
+    pub fn finish(&mut self) {
+        // L1: trailing whitespace is displayed at the paragraph level.
+        self.push_run(self.neutral_start, self.neutral_width, self.state.rtl as u8);
+        self.neutral_width = 0;
+
+        let runs = &mut self.runs[..self.run_count];
+        let start = match runs.first() {
+            Some(run) => run.start,
+            None => return,
+        };
+
+        // L2: reverse sequences of runs, from the highest level to the lowest odd level.
+        let mut order = [0; MAX_RUNS];
+        for (idx, item) in order.iter_mut().enumerate() {
+            *item = idx;
+        }
+        let order = &mut order[..runs.len()];
+
+        let max_level = runs.iter().map(|run| run.level).max().unwrap_or(0);
+        for level in (1..=max_level).rev() {
+            let mut idx = 0;
+            while idx < order.len() {
+                if runs[order[idx]].level < level {
+                    idx += 1;
+                    continue;
+                }
+
+                let seq_start = idx;
+                while idx < order.len() && runs[order[idx]].level >= level {
+                    idx += 1;
+                }
+                order[seq_start..idx].reverse();
+            }
+        }
+
+        let mut x = start;
+        for &idx in order.iter() {
+            runs[idx].visual_start = x;
+            x += runs[idx].width as i32;
+        }
+    }
+
+    /// Returns whether the line is displayed differently from its logical order.
+    pub fn is_reordered(&self) -> bool {
+        !self.disabled && self.runs[..self.run_count].iter().any(|run| run.level > 0)
+    }
+
+    /// Returns the visual ranges covered by a piece of text, as `(x, width)` pairs.
+    pub fn visual_ranges(&self, x: i32, width: u32) -> impl Iterator<Item = (i32, u32)> + '_ {
+        let end = x + width as i32;
+        let runs = &self.runs[..self.run_count];
+        let covered = runs
+            .iter()
+            .map(|run| (run.start, run.start + run.width as i32))
+            .chain(core::iter::once((
+                runs.last().map_or(x, |run| run.start + run.width as i32),
+                i32::MAX,
+            )));
+
+        covered.filter_map(move |(start, run_end)| {
+            let start = start.max(x);
+            let width = (run_end.min(end) - start).max(0) as u32;
+            (width > 0).then(|| (self.map(start, width).0, width))
+        })
+    }
+
+    /// Returns the visual position of a piece of text and whether it is right-to-left.
+    pub fn map(&self, x: i32, width: u32) -> (i32, bool) {
+        let run = self.runs[..self.run_count]
+            .iter()
+            .find(|run| run.start <= x && x < run.start + run.width as i32);
+
+        match run {
+            Some(run) if run.level % 2 == 1 => {
+                let offset = x - run.start;
+                (
+                    run.visual_start + run.width as i32 - offset - width as i32,
+                    true,
+                )
+            }
+            Some(run) => (run.visual_start + x - run.start, false),
+            None => (x, false),
+        }
+    }
+}
+
+/// Element handler that collects the level runs of a line.
+pub(crate) struct LayoutElementHandler<S> {
+    pub text_renderer: S,
+    pub layout: LineLayout,
+    pub glyph_spacing: GlyphSpacing,
+}
+
+impl<S> ElementHandler for LayoutElementHandler<S>
+where
+    S: TextRenderer<Color = <S as CharacterStyle>::Color> + CharacterStyle + SelectFont,
+    <S as CharacterStyle>::Color: Default,
+{
+    type Error = core::convert::Infallible;
+    type Color = <S as CharacterStyle>::Color;
+
+    fn measure(&self, st: &str) -> u32 {
+        str_width(&self.text_renderer, st)
+    }
+
+    fn measure_width_and_left_offset(&self, st: &str) -> (u32, u32) {
+        str_width_and_left_offset(&self.text_renderer, st)
+    }
+
+    fn whitespace(&mut self, _st: &str, _space_count: u32, width: u32) -> Result<(), Self::Error> {
+        self.layout.push_neutral(WS, width);
+        Ok(())
+    }
+
+    fn printed_characters(&mut self, st: &str, width: Option<u32>) -> Result<(), Self::Error> {
+        let width = width.unwrap_or_else(|| self.measure(st));
+        for (cluster, width) in cluster_widths(&self.text_renderer, st, width) {
+            let gap = self.glyph_spacing.consume();
+            self.layout.push_cluster(cluster, width + gap);
+        }
+        Ok(())
+    }
+
+    fn move_cursor(&mut self, by: i32) -> Result<(), Self::Error> {
+        if by < 0 {
+            self.layout.disabled = true;
+        } else {
+            self.layout.push_neutral(S, by as u32);
+        }
+        Ok(())
+    }
+
+    fn inline_object(
+        &mut self,
+        _plugin: usize,
+        _index: usize,
+        size: Size,
+        _alignment: InlineAlignment,
+    ) -> Result<(), Self::Error> {
+        self.layout
+            .push_cluster(OBJECT_REPLACEMENT_CHARACTER, size.width);
+        Ok(())
+    }
+
+    fn change_text_style(
+        &mut self,
+        change: ChangeTextStyle<Self::Color>,
+    ) -> Result<(), Self::Error> {
+        change.apply(&mut self.text_renderer);
+        Ok(())
+    }
+}
+
+/// Draws a piece of text, reordered according to the layout of the line.
+///
+/// Returns the width of the text, including the space inserted between glyphs.
+pub(crate) fn draw_reordered<S, D>(
+    layout: &LineLayout,
+    renderer: &S,
+    display: &mut D,
+    st: &str,
+    position: Point,
+    width: u32,
+    glyph_spacing: &mut GlyphSpacing,
+) -> Result<u32, D::Error>
+where
+    S: TextRenderer,
+    D: embedded_graphics::draw_target::DrawTarget<Color = S::Color>,
+{
+    use embedded_graphics::text::Baseline;
+
+    let mut x = position.x;
+    for (cluster, width) in cluster_widths(renderer, st, width) {
+        let gap = glyph_spacing.consume();
+        let (visual_x, rtl) = layout.map(x, width + gap);
+        // In right-to-left runs, the gap is on the left side of the glyph.
+        let (glyph_x, gap_x) = if rtl {
+            (visual_x + gap as i32, visual_x)
+        } else {
+            (visual_x, visual_x + width as i32)
+        };
+        if gap > 0 {
+            renderer.draw_whitespace(gap, Point::new(gap_x, position.y), Baseline::Top, display)?;
+        }
+        let position = Point::new(glyph_x, position.y);
+
+        let mut chars = cluster.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if rtl && mirrored(c) != c => {
+                let mut buffer = [0; 4];
+                renderer.draw_string(
+                    mirrored(c).encode_utf8(&mut buffer),
+                    position,
+                    Baseline::Top,
+                    display,
+                )?;
+            }
+            _ => {
+                renderer.draw_string(cluster, position, Baseline::Top, display)?;
+            }
+        }
+
+        x += (width + gap) as i32;
+    }
+
+    Ok((x - position.x) as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        rendering::bidi::{clusters, mirrored, LineLayout, ParagraphState},
+        style::TextDirection,
+    };
+
+    /// Lays out a line of fixed width characters and returns it in visual order.
+    #[track_caller]
+    fn assert_visual(direction: TextDirection, text: &str, expected: &str) {
+        let state = ParagraphState::start(direction, text.split(' '));
+        let mut layout = LineLayout::new(state, 0);
+        for cluster in clusters(text) {
+            layout.push_cluster(cluster, 1);
+        }
+        layout.finish();
+
+        let mut visual = clusters(text)
+            .enumerate()
+            .map(|(x, cluster)| {
+                let (x, rtl) = layout.map(x as i32, 1);
+                let mut chars = cluster.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if rtl => (x, mirrored(c).to_string()),
+                    _ => (x, cluster.to_string()),
+                }
+            })
+            .collect::<Vec<_>>();
+        visual.sort_by_key(|(x, _)| *x);
+
+        assert_eq!(
+            visual.into_iter().map(|(_, c)| c).collect::<String>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn left_to_right_text_is_not_reordered() {
+        assert_visual(TextDirection::LeftToRight, "abc (1.5)!", "abc (1.5)!");
+        assert_visual(TextDirection::Auto, "abc (1.5)!", "abc (1.5)!");
+    }
+
+    #[test]
+    fn right_to_left_run_is_reversed() {
+        assert_visual(TextDirection::LeftToRight, "abc אבג def", "abc גבא def");
+        assert_visual(TextDirection::LeftToRight, "abc אבג.", "abc גבא.");
+    }
+
+    #[test]
+    fn right_to_left_paragraph() {
+        assert_visual(TextDirection::RightToLeft, "אבג abc!", "!abc גבא");
+        assert_visual(TextDirection::Auto, "אבג abc!", "!abc גבא");
+    }
+
+    #[test]
+    fn numbers_are_left_to_right() {
+        assert_visual(TextDirection::LeftToRight, "אב 12.5% גד", "דג 12.5% בא");
+        assert_visual(TextDirection::Auto, "אב -1,000", "1,000- בא");
+    }
+
+    #[test]
+    fn arabic_numbers_are_not_strong() {
+        // The direction of the paragraph is set by the first letter, not the leading number.
+        assert_visual(TextDirection::Auto, "١٢ مرحبا", "ابحرم ١٢");
+        assert_visual(TextDirection::Auto, "١٢ abc", "١٢ abc");
+    }
+
+    #[test]
+    fn visual_ranges_are_split_at_runs() {
+        let text = "ab אב";
+        let mut layout = LineLayout::new(
+            ParagraphState::start(TextDirection::LeftToRight, text.split(' ')),
+            0,
+        );
+        for cluster in clusters(text) {
+            layout.push_cluster(cluster, 1);
+        }
+        layout.finish();
+
+        assert_eq!(
+            layout.visual_ranges(1, 3).collect::<Vec<_>>(),
+            [(1, 2), (4, 1)]
+        );
+    }
+
+    #[test]
+    fn brackets_are_mirrored() {
+        assert_visual(TextDirection::Auto, "אב (גד)", "(דג) בא");
+    }
+
+    #[test]
+    fn runs_after_the_last_one_are_merged_into_it() {
+        // The 16th run starts with "ח" and contains the rest of the line.
+        assert_visual(
+            TextDirection::LeftToRight,
+            "aאbבcגdדeהfוgזhחiטjי",
+            "aאbבcגdדeהfוgזhיjטiח",
+        );
+    }
+
+    #[test]
+    fn nonspacing_marks_follow_base_character() {
+        assert_visual(TextDirection::LeftToRight, "a ש\u{5BC}ב", "a בש\u{5BC}");
+    }
+}
@@ -42,6 +42,22 @@ impl LineCursor {
         self.width
     }
 
+    /// Changes the width of the line.
+    pub fn set_width(&mut self, width: u32) {
+        self.width = width;
+    }
+
+    /// Indents the line by reducing its width.
+    ///
+    /// The indentation is on the left side of the line, unless `rtl` is set.
+    pub fn indent(&mut self, by: u32, rtl: bool) {
+        let by = by.min(self.width);
+        if !rtl {
+            self.start.x += by.saturating_as::<i32>();
+        }
+        self.width -= by;
+    }
+
     /// Returns whether the current line has enough space to also include an object of given width.
     pub const fn fits_in_line(&self, width: u32) -> bool {
         width <= self.space()
@@ -95,12 +111,16 @@ pub struct Cursor {
     /// Current cursor position
     pub y: i32,
 
+    /// Horizontal offset of the lines, relative to the left edge of the bounding box.
+    pub x_offset: i32,
+
     top_left: Point,
     bottom: i32,
 
     line_width: u32,
     line_height: u32,
     line_spacing: i32,
+    spacing: LineHeight,
     tab_width: u32,
 }
 
@@ -116,6 +136,7 @@ impl Cursor {
     ) -> Self {
         Self {
             y: bounds.top_left.y,
+            x_offset: 0,
 
             top_left: bounds.top_left,
             bottom: bounds.top_left.y + bounds.size.height.saturating_as::<i32>()
@@ -124,6 +145,7 @@ impl Cursor {
             line_width: bounds.size.width,
             line_height: base_line_height,
             line_spacing: line_height.to_absolute(base_line_height).saturating_as(),
+            spacing: line_height,
             tab_width,
         }
     }
@@ -131,7 +153,7 @@ impl Cursor {
     #[must_use]
     pub(crate) fn line(&self) -> LineCursor {
         LineCursor {
-            start: self.line_start(),
+            start: self.line_start() + Point::new(self.x_offset, 0),
             width: self.line_width,
             position: 0,
             tab_width: self.tab_width,
@@ -168,6 +190,32 @@ impl Cursor {
         self.line_height
     }
 
+    /// Changes the height of the current line, e.g. if it contains a font that is taller than the
+    /// base font.
+    ///
+    /// The distance to the next line is scaled to the new height.
+    #[inline]
+    pub(crate) fn set_line_height(&mut self, line_height: u32) {
+        self.bottom += self.line_height.saturating_as::<i32>() - line_height.saturating_as::<i32>();
+        self.line_height = line_height;
+        self.line_spacing = self.spacing.to_absolute(line_height).saturating_as();
+    }
+
+    /// Allows lines to be displayed the given number of rows above and below the bounding box.
+    #[inline]
+    pub(crate) fn extend(&mut self, above: u32, below: u32) {
+        self.top_left.y -= above.saturating_as::<i32>();
+        self.bottom += below.saturating_as::<i32>();
+    }
+
+    /// Returns the space between lines that is not covered by the glyphs.
+    #[inline]
+    pub(crate) fn leading(&self) -> u32 {
+        self.line_spacing
+            .saturating_sub(self.line_height.saturating_as())
+            .saturating_as()
+    }
+
     /// Starts a new line.
     #[inline]
     pub fn new_line(&mut self) {
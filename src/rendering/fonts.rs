@@ -0,0 +1,242 @@
+//! Switching between the fonts of a text box.
+
+use az::SaturatingAs;
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::Point,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+
+use crate::alignment::BaselineShift;
+
+/// Text renderer that can switch between a set of fonts.
+pub(crate) trait SelectFont {
+    /// Switches to the given font. `None` selects the character style of the text box.
+    fn select_font(&mut self, font: Option<usize>);
+
+    /// Moves the baseline of the following text.
+    fn shift_baseline(&mut self, shift: BaselineShift);
+
+    /// Returns the distance the text is raised by, in pixels.
+    fn baseline_shift(&self) -> i32;
+}
+
+/// Returns the distance between the top of a line and the baseline of the text renderer.
+pub(crate) fn ascent<S: TextRenderer>(text_renderer: &S) -> u32 {
+    let top = |baseline| {
+        text_renderer
+            .measure_string(" ", Point::zero(), baseline)
+            .bounding_box
+            .top_left
+            .y
+    };
+
+    (top(Baseline::Top) - top(Baseline::Alphabetic)).saturating_as()
+}
+
+/// Text renderer that uses one of the fonts registered in a text box.
+///
+/// The active font is created from the registered one by `select`. This allows measuring text
+/// with fonts that can't be cloned, by borrowing them instead.
+#[derive(Clone, Debug)]
+pub(crate) struct Fonts<'f, T, S> {
+    base: &'f T,
+    fonts: &'f [T],
+    current: S,
+    select: fn(&'f T) -> S,
+    shift: BaselineShift,
+}
+
+impl<'f, T, S> Fonts<'f, T, S> {
+    /// Creates a new object that starts with the `base` font.
+    pub fn new(base: &'f T, fonts: &'f [T], select: fn(&'f T) -> S) -> Self {
+        Self {
+            base,
+            fonts,
+            current: select(base),
+            select,
+            shift: BaselineShift::default(),
+        }
+    }
+}
+
+impl<T, S: TextRenderer> SelectFont for Fonts<'_, T, S> {
+    #[inline]
+    fn select_font(&mut self, font: Option<usize>) {
+        let font = font.and_then(|i| self.fonts.get(i)).unwrap_or(self.base);
+        self.current = (self.select)(font);
+    }
+
+    #[inline]
+    fn shift_baseline(&mut self, shift: BaselineShift) {
+        self.shift = shift;
+    }
+
+    #[inline]
+    fn baseline_shift(&self) -> i32 {
+        self.shift.pixels(ascent(&self.current))
+    }
+}
+
+impl<T, S: TextRenderer> TextRenderer for Fonts<'_, T, S> {
+    type Color = S::Color;
+
+    #[inline]
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.current.draw_string(text, position, baseline, target)
+    }
+
+    #[inline]
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.current
+            .draw_whitespace(width, position, baseline, target)
+    }
+
+    #[inline]
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        self.current.measure_string(text, position, baseline)
+    }
+
+    #[inline]
+    fn line_height(&self) -> u32 {
+        self.current.line_height()
+    }
+}
+
+impl<T: Clone, S: CharacterStyle> CharacterStyle for Fonts<'_, T, S> {
+    type Color = <S as CharacterStyle>::Color;
+
+    #[inline]
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.current.set_text_color(text_color);
+    }
+
+    #[inline]
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.current.set_background_color(background_color);
+    }
+
+    #[inline]
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.current.set_underline_color(underline_color);
+    }
+
+    #[inline]
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.current.set_strikethrough_color(strikethrough_color);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        mono_font::{
+            ascii::{FONT_10X20, FONT_6X9},
+            MonoTextStyle,
+        },
+        pixelcolor::BinaryColor,
+        prelude::PixelColor,
+        text::renderer::TextRenderer,
+    };
+
+    use crate::{
+        alignment::BaselineShift,
+        rendering::fonts::{ascent, Fonts, SelectFont},
+        utils::test::{InkTestFont, TestFont},
+    };
+
+    // The text renderers used by the tests don't switch fonts on their own.
+    impl<C: PixelColor> SelectFont for MonoTextStyle<'_, C> {
+        fn select_font(&mut self, _font: Option<usize>) {}
+
+        fn shift_baseline(&mut self, _shift: BaselineShift) {}
+
+        fn baseline_shift(&self) -> i32 {
+            0
+        }
+    }
+
+    impl<C: PixelColor> SelectFont for TestFont<C> {
+        fn select_font(&mut self, _font: Option<usize>) {}
+
+        fn shift_baseline(&mut self, _shift: BaselineShift) {}
+
+        fn baseline_shift(&self) -> i32 {
+            0
+        }
+    }
+
+    impl<C: PixelColor> SelectFont for InkTestFont<C> {
+        fn select_font(&mut self, _font: Option<usize>) {}
+
+        fn shift_baseline(&mut self, _shift: BaselineShift) {}
+
+        fn baseline_shift(&self) -> i32 {
+            0
+        }
+    }
+
+    #[test]
+    fn select_registered_fonts() {
+        let base = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let fonts = [MonoTextStyle::new(&FONT_10X20, BinaryColor::On)];
+
+        let mut renderer = Fonts::new(&base, &fonts, MonoTextStyle::clone);
+        assert_eq!(renderer.line_height(), 9);
+        assert_eq!(ascent(&renderer), 6);
+
+        renderer.select_font(Some(0));
+        assert_eq!(renderer.line_height(), 20);
+        assert_eq!(ascent(&renderer), 15);
+
+        // Unknown fonts fall back to the base font.
+        renderer.select_font(Some(1));
+        assert_eq!(renderer.line_height(), 9);
+
+        renderer.select_font(Some(0));
+        renderer.select_font(None);
+        assert_eq!(renderer.line_height(), 9);
+    }
+
+    #[test]
+    fn baseline_shift_follows_font() {
+        let base = MonoTextStyle::new(&FONT_6X9, BinaryColor::On);
+        let fonts = [MonoTextStyle::new(&FONT_10X20, BinaryColor::On)];
+
+        let mut renderer = Fonts::new(&base, &fonts, MonoTextStyle::clone);
+        assert_eq!(renderer.baseline_shift(), 0);
+
+        renderer.shift_baseline(BaselineShift::Superscript);
+        assert_eq!(renderer.baseline_shift(), 3);
+
+        renderer.shift_baseline(BaselineShift::Subscript);
+        assert_eq!(renderer.baseline_shift(), -2);
+
+        renderer.select_font(Some(0));
+        assert_eq!(renderer.baseline_shift(), -5);
+
+        renderer.shift_baseline(BaselineShift::Pixels(4));
+        assert_eq!(renderer.baseline_shift(), 4);
+    }
+}
@@ -1,17 +1,27 @@
 //! Line rendering.
 
 use crate::{
-    parser::{ChangeTextStyle, Parser},
+    alignment::{BaselineShift, InlineAlignment},
+    parser::{ChangeTextStyle, Parser, OBJECT_REPLACEMENT_CHARACTER},
     plugin::{PluginMarker as Plugin, PluginWrapper, ProcessingState},
     rendering::{
         cursor::LineCursor,
+        fonts::{ascent, SelectFont},
         line_iter::{ElementHandler, LineElementParser, LineEndType},
+        space_config::GlyphSpacing,
     },
-    style::TextBoxStyle,
-    utils::{str_width, str_width_and_left_offset},
+    style::{ElidedText, LineMeasurement, LineWidths, TextBoxStyle, UNLIMITED_LINE_WIDTH},
+    utils::{grapheme_widths, str_width, str_width_and_left_offset},
 };
+#[cfg(feature = "bidi")]
+use crate::{
+    parser::Token,
+    rendering::bidi::{draw_reordered, LayoutElementHandler, LineLayout, ParagraphState},
+    style::TextDirection,
+};
+use az::SaturatingAs;
 use embedded_graphics::{
-    draw_target::DrawTarget,
+    draw_target::{DrawTarget, DrawTargetExt},
     geometry::Point,
     prelude::{PixelColor, Size},
     primitives::Rectangle,
@@ -25,9 +35,11 @@ impl<C> ChangeTextStyle<C>
 where
     C: PixelColor + Default,
 {
-    pub(crate) fn apply<S: CharacterStyle<Color = C>>(self, text_renderer: &mut S) {
+    pub(crate) fn apply<S: CharacterStyle<Color = C> + SelectFont>(self, text_renderer: &mut S) {
         match self {
             ChangeTextStyle::Reset => {
+                text_renderer.select_font(None);
+                text_renderer.shift_baseline(BaselineShift::None);
                 text_renderer.set_text_color(Some(C::default()));
                 text_renderer.set_background_color(None);
                 text_renderer.set_underline_color(DecorationColor::None);
@@ -37,10 +49,83 @@ where
             ChangeTextStyle::BackgroundColor(color) => text_renderer.set_background_color(color),
             ChangeTextStyle::Underline(color) => text_renderer.set_underline_color(color),
             ChangeTextStyle::Strikethrough(color) => text_renderer.set_strikethrough_color(color),
+            ChangeTextStyle::Font(font) => text_renderer.select_font(font),
+            ChangeTextStyle::BaselineShift(shift) => text_renderer.shift_baseline(shift),
         }
     }
 }
 
+/// Fills an area with the background color of the text renderer.
+///
+/// Nothing is drawn if the text renderer has no background color.
+pub(crate) fn fill_background<S, D>(
+    text_renderer: &S,
+    area: Rectangle,
+    display: &mut D,
+) -> Result<(), D::Error>
+where
+    S: TextRenderer,
+    D: DrawTarget<Color = S::Color>,
+{
+    if area.is_zero_sized() {
+        return Ok(());
+    }
+
+    let mut display = display.clipped(&area);
+    let row_height = text_renderer.line_height().max(1).saturating_as::<i32>();
+    let rows = area.rows().step_by(row_height as usize);
+    for y in rows {
+        text_renderer.draw_whitespace(
+            area.size.width,
+            Point::new(area.top_left.x, y),
+            Baseline::Top,
+            &mut display,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Fills the parts of the line that are left and right of the text with the background color.
+///
+/// The space before the text is filled using `start_renderer`, the text renderer at the start of
+/// the line, and the space after the text is filled using `end_renderer`. The line is limited by
+/// the bounding box of the display.
+fn fill_line_sides<S, D>(
+    start_renderer: &S,
+    end_renderer: &S,
+    display: &mut D,
+    y: i32,
+    line_height: u32,
+    text: core::ops::Range<i32>,
+    rtl: bool,
+) -> Result<(), D::Error>
+where
+    S: TextRenderer,
+    D: DrawTarget<Color = S::Color>,
+{
+    let bounds = display.bounding_box();
+    let left = bounds.top_left.x;
+    let right = left + bounds.size.width.saturating_as::<i32>();
+
+    let (left_renderer, right_renderer) = if rtl {
+        (end_renderer, start_renderer)
+    } else {
+        (start_renderer, end_renderer)
+    };
+
+    for (renderer, from, to) in [
+        (left_renderer, left, text.start.min(right)),
+        (right_renderer, text.end.max(left), right),
+    ] {
+        let width = (to - from).max(0) as u32;
+        let area = Rectangle::new(Point::new(from, y), Size::new(width, line_height));
+        fill_background(renderer, area, display)?;
+    }
+
+    Ok(())
+}
+
 /// Render a single line of styled text.
 pub(crate) struct StyledLineRenderer<'a, 'b, 'c, S, M>
 where
@@ -50,6 +135,9 @@ where
     pub(crate) cursor: LineCursor,
     pub(crate) state: &'c mut LineRenderState<'a, 'b, S, M>,
     pub(crate) style: &'c TextBoxStyle,
+
+    /// String that replaces the end of the line, if the text is cut off after it.
+    pub(crate) ellipsis: Option<&'static str>,
 }
 
 #[derive(Clone)]
@@ -62,6 +150,81 @@ where
     pub text_renderer: S,
     pub end_type: LineEndType,
     pub plugin: &'b PluginWrapper<'a, M, S::Color>,
+
+    /// True if the next line is the first line of a paragraph.
+    pub first_line: bool,
+
+    /// Preserved indentation of the wrapped lines of the current paragraph.
+    pub wrap_indent: u32,
+
+    /// Line breaks chosen for the current paragraph.
+    pub line_widths: LineWidths,
+
+    /// Bidi state of the current paragraph.
+    #[cfg(feature = "bidi")]
+    pub bidi: ParagraphState,
+}
+
+impl<'a, 'b, S, M> LineRenderState<'a, 'b, S, M>
+where
+    S: TextRenderer + Clone,
+    M: Plugin<'a, S::Color>,
+{
+    pub fn new(
+        parser: Parser<'a, S::Color>,
+        text_renderer: S,
+        plugin: &'b PluginWrapper<'a, M, S::Color>,
+    ) -> Self {
+        Self {
+            parser,
+            text_renderer,
+            end_type: LineEndType::EndOfText,
+            plugin,
+            first_line: true,
+            wrap_indent: 0,
+            line_widths: LineWidths::default(),
+            #[cfg(feature = "bidi")]
+            bidi: ParagraphState::new(),
+        }
+    }
+
+    /// Returns whether the current paragraph is right-to-left.
+    fn is_rtl(&self) -> bool {
+        #[cfg(feature = "bidi")]
+        {
+            self.bidi.rtl
+        }
+
+        #[cfg(not(feature = "bidi"))]
+        {
+            false
+        }
+    }
+
+    /// Resolves the direction of the paragraph if the next line starts a new one.
+    #[cfg(feature = "bidi")]
+    fn start_paragraph(&mut self, direction: TextDirection) {
+        if !matches!(self.end_type, LineEndType::NewLine | LineEndType::EndOfText) {
+            return;
+        }
+
+        let plugin = self.plugin.clone();
+        plugin.set_state(ProcessingState::Measure);
+        let mut parser = self.parser.clone();
+
+        let words = core::iter::from_fn(|| {
+            let token = plugin.peek_token(&mut parser);
+            plugin.consume_peeked_token();
+
+            match token? {
+                Token::NewLine => None,
+                Token::Word(word) => Some(word),
+                _ => Some(""),
+            }
+        });
+
+        self.bidi = ParagraphState::start(direction, words);
+    }
 }
 
 struct RenderElementHandler<'a, 'b, F, D, M>
@@ -73,6 +236,25 @@ where
     display: &'b mut D,
     pos: Point,
     plugin: &'b PluginWrapper<'a, M, F::Color>,
+
+    /// Distance between the top of the line and the baseline shared by its fonts.
+    baseline: u32,
+
+    /// Height of the line.
+    line_height: u32,
+
+    /// Offset of the top of the current font, which aligns it to the shared baseline.
+    baseline_offset: Point,
+
+    /// Fill the parts of the line above and below the glyphs with the background color.
+    fill_background: bool,
+
+    /// Space inserted between glyphs of justified lines.
+    glyph_spacing: GlyphSpacing,
+
+    /// Visual order of the line, if it contains bidirectional text.
+    #[cfg(feature = "bidi")]
+    layout: Option<&'b LineLayout>,
 }
 
 impl<'a, 'b, F, D, M> RenderElementHandler<'a, 'b, F, D, M>
@@ -81,8 +263,54 @@ where
     D: DrawTarget<Color = <F as TextRenderer>::Color>,
     M: Plugin<'a, <F as TextRenderer>::Color>,
 {
+    /// Fills the parts of the line above and below a piece of content with the background color.
+    ///
+    /// `x` is the logical position of the content and `cell` is the vertical range it covers.
+    fn fill_gaps(
+        &mut self,
+        x: i32,
+        width: u32,
+        cell: core::ops::Range<i32>,
+    ) -> Result<(), D::Error> {
+        if !self.fill_background || width == 0 {
+            return Ok(());
+        }
+
+        let top = self.pos.y;
+        let bottom = top + self.line_height.saturating_as::<i32>();
+        for (from, to) in [(top, cell.start.min(bottom)), (cell.end.max(top), bottom)] {
+            let height = (to - from).max(0) as u32;
+            if height == 0 {
+                continue;
+            }
+
+            #[cfg(feature = "bidi")]
+            if let Some(layout) = self.layout {
+                for (x, width) in layout.visual_ranges(x, width) {
+                    let area = Rectangle::new(Point::new(x, from), Size::new(width, height));
+                    fill_background(self.text_renderer, area, self.display)?;
+                }
+                continue;
+            }
+
+            let area = Rectangle::new(Point::new(x, from), Size::new(width, height));
+            fill_background(self.text_renderer, area, self.display)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fills the gaps around text that was drawn using the current font and moves the cursor.
+    fn post_print_text(&mut self, width: u32, st: &str) -> Result<(), D::Error> {
+        let top = self.pos.y + self.baseline_offset.y;
+        let cell = top..top + self.text_renderer.line_height().saturating_as::<i32>();
+        self.fill_gaps(self.pos.x, width, cell)?;
+
+        self.post_print(width, st)
+    }
+
     fn post_print(&mut self, width: u32, st: &str) -> Result<(), D::Error> {
-        let bounds = Rectangle::new(self.pos, Size::new(width, self.text_renderer.line_height()));
+        let bounds = Rectangle::new(self.pos, Size::new(width, self.line_height));
 
         self.pos += Point::new(width as i32, 0);
 
@@ -93,7 +321,7 @@ where
 
 impl<'a, 'c, F, D, M> ElementHandler for RenderElementHandler<'a, 'c, F, D, M>
 where
-    F: CharacterStyle + TextRenderer,
+    F: CharacterStyle + TextRenderer + SelectFont,
     D: DrawTarget<Color = <F as TextRenderer>::Color>,
     M: Plugin<'a, <F as TextRenderer>::Color>,
     <F as CharacterStyle>::Color: Default,
@@ -111,21 +339,71 @@ where
 
     fn whitespace(&mut self, st: &str, _space_count: u32, width: u32) -> Result<(), Self::Error> {
         if width > 0 {
-            self.text_renderer
-                .draw_whitespace(width, self.pos, Baseline::Top, self.display)?;
+            #[allow(unused_mut)]
+            let mut pos = self.pos;
+
+            #[cfg(feature = "bidi")]
+            if let Some(layout) = self.layout {
+                pos.x = layout.map(pos.x, width).0;
+            }
+
+            self.text_renderer.draw_whitespace(
+                width,
+                pos + self.baseline_offset,
+                Baseline::Top,
+                self.display,
+            )?;
         }
 
-        self.post_print(width, st)
+        self.post_print_text(width, st)
     }
 
     fn printed_characters(&mut self, st: &str, width: Option<u32>) -> Result<(), Self::Error> {
-        let render_width =
-            self.text_renderer
-                .draw_string(st, self.pos, Baseline::Top, self.display)?;
+        #[cfg(feature = "bidi")]
+        if let Some(layout) = self.layout {
+            let width = width.unwrap_or_else(|| self.measure(st));
+            let width = draw_reordered(
+                layout,
+                self.text_renderer,
+                self.display,
+                st,
+                self.pos + self.baseline_offset,
+                width,
+                &mut self.glyph_spacing,
+            )?;
+
+            return self.post_print_text(width, st);
+        }
+
+        if !self.glyph_spacing.is_none() {
+            let width = width.unwrap_or_else(|| self.measure(st));
+            let mut pos = self.pos + self.baseline_offset;
+            for (grapheme, width) in grapheme_widths(self.text_renderer, st, width) {
+                self.text_renderer
+                    .draw_string(grapheme, pos, Baseline::Top, self.display)?;
+                pos.x += width as i32;
+
+                let gap = self.glyph_spacing.consume();
+                if gap > 0 {
+                    self.text_renderer
+                        .draw_whitespace(gap, pos, Baseline::Top, self.display)?;
+                    pos.x += gap as i32;
+                }
+            }
+
+            return self.post_print_text((pos - self.pos).x as u32, st);
+        }
+
+        let render_width = self.text_renderer.draw_string(
+            st,
+            self.pos + self.baseline_offset,
+            Baseline::Top,
+            self.display,
+        )?;
 
         let width = width.unwrap_or((render_width - self.pos).x as u32);
 
-        self.post_print(width, st)
+        self.post_print_text(width, st)
     }
 
     fn move_cursor(&mut self, by: i32) -> Result<(), Self::Error> {
@@ -134,18 +412,63 @@ where
         Ok(())
     }
 
+    fn inline_object(
+        &mut self,
+        plugin: usize,
+        index: usize,
+        size: Size,
+        alignment: InlineAlignment,
+    ) -> Result<(), Self::Error> {
+        #[allow(unused_mut)]
+        let mut pos = self.pos;
+
+        #[cfg(feature = "bidi")]
+        if let Some(layout) = self.layout {
+            pos.x = layout.map(pos.x, size.width).0;
+        }
+
+        pos.y += alignment.offset(size.height, self.baseline, self.line_height);
+        self.plugin
+            .draw_inline_object(self.display, plugin, index, Rectangle::new(pos, size))?;
+        self.fill_gaps(
+            self.pos.x,
+            size.width,
+            pos.y..pos.y + size.height.saturating_as::<i32>(),
+        )?;
+
+        self.post_print(size.width, OBJECT_REPLACEMENT_CHARACTER)
+    }
+
     fn change_text_style(
         &mut self,
         change: ChangeTextStyle<<F as CharacterStyle>::Color>,
     ) -> Result<(), Self::Error> {
+        let baseline_changed = matches!(
+            change,
+            ChangeTextStyle::Reset | ChangeTextStyle::Font(_) | ChangeTextStyle::BaselineShift(_)
+        );
         change.apply(self.text_renderer);
+        if baseline_changed {
+            self.baseline_offset = baseline_offset(self.baseline, self.text_renderer);
+        }
         Ok(())
     }
 }
 
+/// Returns the offset that moves the top of the current font so that its baseline is at the given
+/// distance from the top of the line.
+fn baseline_offset<F: TextRenderer + SelectFont>(baseline: u32, text_renderer: &F) -> Point {
+    Point::new(
+        0,
+        baseline.saturating_as::<i32>()
+            - ascent(text_renderer).saturating_as::<i32>()
+            - text_renderer.baseline_shift(),
+    )
+}
+
 impl<'a, 'b, 'c, F, M> StyledLineRenderer<'a, 'b, 'c, F, M>
 where
-    F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle,
+    F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle + SelectFont,
     M: Plugin<'a, <F as TextRenderer>::Color> + Plugin<'a, <F as CharacterStyle>::Color>,
     <F as CharacterStyle>::Color: Default,
 {
@@ -154,51 +477,252 @@ where
     where
         D: DrawTarget<Color = <F as CharacterStyle>::Color>,
     {
+        #[cfg(feature = "bidi")]
+        self.state.start_paragraph(self.style.direction);
+        let rtl = self.state.is_rtl();
+
+        let indent = self.style.indent(
+            self.state.plugin,
+            &self.state.text_renderer,
+            self.state.first_line,
+            self.state.wrap_indent,
+        );
+        self.cursor.indent(indent, rtl);
+
         let LineRenderState {
             ref mut parser,
             ref mut text_renderer,
+            ref mut line_widths,
             plugin,
             ..
         } = self.state;
 
+        if let Some(ellipsis) = self.ellipsis {
+            let ellipsis_width = str_width(text_renderer, ellipsis);
+            self.cursor
+                .set_width(self.cursor.line_width().saturating_sub(ellipsis_width));
+        }
+
         let lm = {
             // Ensure the clone lives for as short as possible.
             let mut cloned_parser = parser.clone();
             let measure_plugin = plugin.clone();
             measure_plugin.set_state(ProcessingState::Measure);
-            self.style.measure_line(
+            self.style.measure_paragraph_line(
+                line_widths,
                 &measure_plugin,
-                text_renderer,
+                &mut text_renderer.clone(),
                 &mut cloned_parser,
                 self.cursor.line_width(),
             )
         };
 
-        let (left, space_config) = self.style.alignment.place_line(text_renderer, lm);
+        if self.state.first_line {
+            self.state.wrap_indent = self.style.wrap_indent(&lm);
+        }
+
+        let (left, space_config, glyph_spacing) = self.style.line_alignment(&lm).place_line(
+            text_renderer,
+            lm,
+            rtl,
+            self.style.justification,
+            self.style.max_word_spacing,
+        );
 
         self.cursor.move_cursor(left).ok();
 
+        // Make sure a line that was wrapped early isn't filled with more text, unless its spaces
+        // are stretched to the full width.
+        if !self.style.line_breaking.wraps() {
+            self.cursor.set_width(UNLIMITED_LINE_WIDTH);
+        } else if lm.wrap_width < lm.max_line_width {
+            if !space_config.is_justified() {
+                self.cursor
+                    .set_width(left.saturating_as::<u32>() + lm.wrap_width);
+            } else if !glyph_spacing.is_none() {
+                // The space between glyphs is not tracked by the cursor.
+                self.cursor
+                    .set_width(lm.max_line_width - glyph_spacing.width());
+            }
+        }
+
+        #[cfg(feature = "bidi")]
+        let layout = if self.state.bidi.needs_layout() {
+            let mut layout_handler = LayoutElementHandler {
+                text_renderer: text_renderer.clone(),
+                layout: LineLayout::new(self.state.bidi, self.cursor.pos().x),
+                glyph_spacing,
+            };
+
+            let mut cloned_parser = parser.clone();
+            let layout_plugin = plugin.clone();
+            LineElementParser::new(
+                &mut cloned_parser,
+                &layout_plugin,
+                self.cursor.clone(),
+                space_config,
+                self.style,
+            )
+            .process(&mut layout_handler)
+            .ok();
+            if let Some(ellipsis) = self.ellipsis {
+                layout_handler.printed_characters(ellipsis, None).ok();
+            }
+
+            let mut layout = layout_handler.layout;
+            layout.finish();
+            self.state.bidi = layout.paragraph_state();
+
+            Some(layout).filter(LineLayout::is_reordered)
+        } else {
+            None
+        };
+
+        let start_renderer = self
+            .style
+            .fill_line_background
+            .then(|| text_renderer.clone());
+        let text_start = self.cursor.pos();
         let mut render_element_handler = RenderElementHandler {
+            baseline_offset: baseline_offset(lm.baseline, text_renderer),
+            baseline: lm.baseline,
+            line_height: lm.line_height,
+            fill_background: self.style.fill_line_background,
             text_renderer,
             display,
-            pos: self.cursor.pos(),
+            pos: text_start,
             plugin: *plugin,
+            glyph_spacing,
+            #[cfg(feature = "bidi")]
+            layout: layout.as_ref(),
         };
         let end_type =
             LineElementParser::new(parser, plugin, self.cursor, space_config, self.style)
                 .process(&mut render_element_handler)?;
 
+        if let Some(ellipsis) = self.ellipsis {
+            render_element_handler.printed_characters(ellipsis, None)?;
+        }
+
+        let end_pos = render_element_handler.pos;
+        if let Some(start_renderer) = start_renderer {
+            fill_line_sides(
+                &start_renderer,
+                text_renderer,
+                display,
+                text_start.y,
+                lm.line_height,
+                text_start.x..end_pos.x,
+                rtl,
+            )?;
+        }
+
         if end_type == LineEndType::EndOfText {
-            let end_pos = render_element_handler.pos;
             plugin.post_render(
                 display,
                 text_renderer,
                 None,
-                Rectangle::new(end_pos, Size::new(0, text_renderer.line_height())),
+                Rectangle::new(end_pos, Size::new(0, lm.line_height)),
             )?;
         }
 
         self.state.end_type = end_type;
+        self.state.first_line = end_type.starts_paragraph(self.state.first_line);
+
+        Ok(())
+    }
+
+    /// Draws a line of text with its middle part replaced by an ellipsis.
+    #[inline]
+    pub(crate) fn draw_elided<D>(
+        mut self,
+        display: &mut D,
+        text: ElidedText<'_>,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = <F as CharacterStyle>::Color>,
+    {
+        #[cfg(feature = "bidi")]
+        self.state.start_paragraph(self.style.direction);
+        let rtl = self.state.is_rtl();
+
+        let indent = self.style.indent(
+            self.state.plugin,
+            &self.state.text_renderer,
+            self.state.first_line,
+            self.state.wrap_indent,
+        );
+        self.cursor.indent(indent, rtl);
+
+        let LineRenderState {
+            ref mut text_renderer,
+            plugin,
+            ..
+        } = self.state;
+
+        let lm = LineMeasurement {
+            max_line_width: self.cursor.line_width(),
+            wrap_width: self.cursor.line_width(),
+            width: text.width(text_renderer),
+            line_end_type: LineEndType::EndOfText,
+            space_count: 0,
+            glyph_count: 0,
+            leading_width: 0,
+            ink_bounds: None,
+            baseline: ascent(text_renderer),
+            line_height: text_renderer.line_height(),
+        };
+        let (left, _, _) = self.style.line_alignment(&lm).place_line(
+            text_renderer,
+            lm,
+            rtl,
+            self.style.justification,
+            self.style.max_word_spacing,
+        );
+
+        let start_renderer = self
+            .style
+            .fill_line_background
+            .then(|| text_renderer.clone());
+        let text_start = self.cursor.pos() + Point::new(left, 0);
+        let mut render_element_handler = RenderElementHandler {
+            text_renderer,
+            display,
+            pos: text_start,
+            plugin: *plugin,
+            baseline: lm.baseline,
+            line_height: lm.line_height,
+            baseline_offset: Point::zero(),
+            fill_background: self.style.fill_line_background,
+            glyph_spacing: GlyphSpacing::none(),
+            #[cfg(feature = "bidi")]
+            layout: None,
+        };
+        for part in [text.start, text.ellipsis, text.end] {
+            render_element_handler.printed_characters(part, None)?;
+        }
+
+        let end_pos = render_element_handler.pos;
+        if let Some(start_renderer) = start_renderer {
+            fill_line_sides(
+                &start_renderer,
+                text_renderer,
+                display,
+                text_start.y,
+                lm.line_height,
+                text_start.x..end_pos.x,
+                rtl,
+            )?;
+        }
+
+        plugin.post_render(
+            display,
+            text_renderer,
+            None,
+            Rectangle::new(end_pos, Size::new(0, lm.line_height)),
+        )?;
+
+        self.state.end_type = LineEndType::EndOfText;
 
         Ok(())
     }
@@ -211,8 +735,8 @@ mod test {
         plugin::{NoPlugin, PluginWrapper},
         rendering::{
             cursor::LineCursor,
+            fonts::SelectFont,
             line::{LineRenderState, StyledLineRenderer},
-            line_iter::LineEndType,
         },
         style::{TabSize, TextBoxStyle, TextBoxStyleBuilder},
         utils::test::size_for,
@@ -233,7 +757,7 @@ mod test {
         style: TextBoxStyle,
         pattern: &[&str],
     ) where
-        S: TextRenderer<Color = <S as CharacterStyle>::Color> + CharacterStyle,
+        S: TextRenderer<Color = <S as CharacterStyle>::Color> + CharacterStyle + SelectFont,
         <S as CharacterStyle>::Color: embedded_graphics::mock_display::ColorMapping + Default,
     {
         let parser = Parser::parse(text);
@@ -244,17 +768,13 @@ mod test {
 
         let plugin = PluginWrapper::new(NoPlugin::new());
 
-        let mut state = LineRenderState {
-            parser,
-            text_renderer: character_style,
-            end_type: LineEndType::EndOfText,
-            plugin: &plugin,
-        };
+        let mut state = LineRenderState::new(parser, character_style, &plugin);
 
         let renderer = StyledLineRenderer {
             cursor,
             state: &mut state,
             style: &style,
+            ellipsis: None,
         };
         let mut display = MockDisplay::new();
         display.set_allow_overdraw(true);
@@ -5,13 +5,15 @@
 //! handling tab characters, soft wrapping characters, non-breaking spaces, etc.
 
 use crate::{
-    parser::{ChangeTextStyle, Parser, Token, SPEC_CHAR_NBSP},
+    alignment::InlineAlignment,
+    parser::{cjk::HANGING_PUNCTUATION, ChangeTextStyle, Parser, Token, SPEC_CHAR_NBSP},
     plugin::{PluginMarker as Plugin, PluginWrapper},
     rendering::{cursor::LineCursor, space_config::SpaceConfig},
-    style::TextBoxStyle,
+    style::{LineBreaking, TextBoxStyle, WordBreak},
+    utils::graphemes,
 };
 use az::SaturatingAs;
-use embedded_graphics::prelude::PixelColor;
+use embedded_graphics::{geometry::Size, prelude::PixelColor};
 
 /// Parser to break down a line into primitive elements used by measurement and rendering.
 #[derive(Debug)]
@@ -39,6 +41,20 @@ pub enum LineEndType {
     LineBreak,
 }
 
+impl LineEndType {
+    /// Returns whether the next line is the first line of a paragraph.
+    ///
+    /// `first_line` is true if the line that ended is the first line of a paragraph. Carriage
+    /// returns continue on the same line.
+    pub fn starts_paragraph(self, first_line: bool) -> bool {
+        match self {
+            LineEndType::NewLine | LineEndType::EndOfText => true,
+            LineEndType::LineBreak => false,
+            LineEndType::CarriageReturn => first_line,
+        }
+    }
+}
+
 pub trait ElementHandler {
     type Error;
     type Color: PixelColor;
@@ -64,6 +80,17 @@ pub trait ElementHandler {
         Ok(())
     }
 
+    /// An inline object with the given size, produced by the plugin at position `plugin`.
+    fn inline_object(
+        &mut self,
+        _plugin: usize,
+        _index: usize,
+        _size: Size,
+        _alignment: InlineAlignment,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
     /// Text style change
     fn change_text_style(
         &mut self,
@@ -104,6 +131,7 @@ where
 
         let mut width = 0;
         let mut width_set = false;
+        let mut hanging = 0;
 
         loop {
             lookahead.consume_peeked_token();
@@ -111,14 +139,22 @@ where
                 Some(Token::Word(w)) => {
                     width += handler.measure(w);
                     width_set = true;
+                    hanging = self.hanging_width(handler, w);
+                }
+
+                Some(Token::InlineObject { size, .. }) => {
+                    width += size.width;
+                    width_set = true;
+                    hanging = 0;
                 }
 
+                Some(Token::Break("")) => return Some(width - hanging),
                 Some(Token::Break(w)) => return Some(width + handler.measure(w)),
                 Some(Token::ChangeTextStyle(_)) | Some(Token::MoveCursor { .. }) => {}
 
                 _ => {
                     return match width_set {
-                        true => Some(width),
+                        true => Some(width - hanging),
                         false => None,
                     };
                 }
@@ -139,10 +175,10 @@ where
         handler: &E,
         w: &'a str,
     ) -> (&'a str, &'a str) {
-        for (idx, c) in w.char_indices() {
+        for (idx, grapheme) in graphemes(w) {
             let width = handler.measure(unsafe {
                 // SAFETY: we are working on character boundaries
-                w.get_unchecked(0..idx + c.len_utf8())
+                w.get_unchecked(0..idx + grapheme.len())
             });
             if !self.cursor.fits_in_line(width) {
                 unsafe {
@@ -158,6 +194,66 @@ where
         (w, "")
     }
 
+    /// Returns the width of the punctuation at the end of the word that may hang into the margin.
+    fn hanging_width<E: ElementHandler>(&self, handler: &E, w: &str) -> u32 {
+        if self.style.line_breaking != LineBreaking::Cjk {
+            return 0;
+        }
+
+        let trimmed = w.trim_end_matches(HANGING_PUNCTUATION);
+        if trimmed.len() == w.len() {
+            0
+        } else {
+            handler.measure(w).saturating_sub(handler.measure(trimmed))
+        }
+    }
+
+    /// Returns whether a word that doesn't fit into the line should be displayed anyway.
+    fn word_overflows(&self, width: u32) -> bool {
+        match self.style.word_break {
+            WordBreak::KeepAll => self.empty,
+            WordBreak::Clip => width > self.cursor.line_width(),
+            WordBreak::BreakWord | WordBreak::BreakAll => false,
+        }
+    }
+
+    /// Returns the width of the tokens that continue the current word, if a plugin split it.
+    fn split_word_rest_width<E: ElementHandler>(&self, handler: &E) -> Option<u32> {
+        let lookahead = self.plugin.clone();
+        let mut lookahead_parser = self.parser.clone();
+
+        let mut width = None;
+        loop {
+            lookahead.consume_peeked_token();
+            match lookahead.peek_token(&mut lookahead_parser) {
+                Some(Token::Word(w)) if lookahead.continues_word() => {
+                    width = Some(width.unwrap_or(0) + handler.measure(w));
+                }
+                Some(Token::InlineObject { size, .. }) if lookahead.continues_word() => {
+                    width = Some(width.unwrap_or(0) + size.width);
+                }
+                Some(Token::ChangeTextStyle(_)) => {}
+                _ => return width,
+            }
+        }
+    }
+
+    /// Returns whether a word that a plugin split into multiple tokens should be moved to the
+    /// next line, even though its first part fits into the current line.
+    fn split_word_wraps<E: ElementHandler>(&self, handler: &E, width: u32) -> bool {
+        if self.style.word_break == WordBreak::BreakAll || !self.cursor.fits_in_line(width) {
+            return false;
+        }
+
+        match self.split_word_rest_width(handler) {
+            Some(rest) => {
+                let width = width + rest;
+                !self.cursor.fits_in_line(width) && !self.word_overflows(width)
+            }
+            None => false,
+        }
+    }
+
     fn next_word_fits<E: ElementHandler>(&self, handler: &E) -> bool {
         let mut cursor = self.cursor.clone();
 
@@ -168,14 +264,48 @@ where
         let mut lookahead_parser = self.parser.clone();
 
         let mut exit = false;
+        let mut in_word = false;
         while !exit {
             lookahead.consume_peeked_token();
             let width = match lookahead.peek_token(&mut lookahead_parser) {
-                Some(Token::Word(w)) | Some(Token::Break(w)) => {
+                Some(Token::Word(w)) if !in_word || lookahead.continues_word() => {
+                    let width = handler.measure(w);
+                    match self.style.word_break {
+                        // The word will be broken if only its first character fits.
+                        WordBreak::BreakAll => {
+                            exit = true;
+                            let first = graphemes(w).next().map_or("", |(_, g)| g);
+                            handler.measure(first).saturating_as()
+                        }
+                        // The word will be displayed anyway.
+                        WordBreak::Clip if width > cursor.line_width() => {
+                            exit = true;
+                            0
+                        }
+                        // A plugin may have split the word into multiple tokens.
+                        _ => {
+                            in_word = true;
+                            width.saturating_as()
+                        }
+                    }
+                }
+
+                Some(Token::Break(w)) => {
                     exit = true;
                     handler.measure(w).saturating_as()
                 }
 
+                Some(Token::InlineObject { size, .. })
+                    if !in_word || lookahead.continues_word() =>
+                {
+                    in_word = true;
+                    size.width.saturating_as()
+                }
+
+                Some(Token::ChangeTextStyle(_)) => 0,
+
+                _ if in_word => return true,
+
                 Some(Token::Whitespace(n, _)) => spaces.consume(n).saturating_as(),
                 Some(Token::Tab) => cursor.next_tab_width().saturating_as(),
 
@@ -183,8 +313,6 @@ where
                     chars * handler.measure(" ").saturating_as::<i32>()
                 }
 
-                Some(Token::ChangeTextStyle(_)) => 0,
-
                 _ => return false,
             };
 
@@ -307,7 +435,9 @@ where
                             let width = handler.measure(c);
                             if self.move_cursor_forward(width).is_ok() {
                                 if let Some(Token::Break(c)) = self.plugin.render_token(token) {
-                                    handler.printed_characters(c, Some(width))?;
+                                    if !c.is_empty() {
+                                        handler.printed_characters(c, Some(width))?;
+                                    }
                                 }
                                 self.consume_token();
                             }
@@ -334,20 +464,40 @@ where
                         handler.measure(w)
                     };
 
+                    if !self.empty
+                        && !self.plugin.continues_word()
+                        && self.split_word_wraps(handler, width)
+                    {
+                        return Ok(LineEndType::LineBreak);
+                    }
+
                     let (word, remainder) = if self.move_cursor_forward(width).is_ok() {
                         // We can move the cursor here since `process_word()`
                         // doesn't depend on it.
                         (w, "")
-                    } else if self.empty {
-                        // This word does not fit into an empty line. Find longest part
+                    } else if self.word_overflows(width) {
+                        // The word is displayed in full and cut off at the end of the line.
+                        // Nothing else fits after it.
+                        let _ = self.move_cursor_forward(self.cursor.space());
+                        (w, "")
+                    } else if self
+                        .cursor
+                        .fits_in_line(width - self.hanging_width(handler, w))
+                    {
+                        // Punctuation at the end of the word hangs into the margin.
+                        let _ = self.move_cursor_forward(self.cursor.space());
+                        (w, "")
+                    } else if self.empty || self.style.word_break == WordBreak::BreakAll {
+                        // This word does not fit into the line. Find longest part
                         // that fits and push the rest to the next line.
                         match self.longest_fitting_substr(handler, w) {
-                            ("", _) => {
+                            ("", _) if self.empty => {
                                 // Weird case where width doesn't permit drawing anything.
                                 // End here to prevent infinite looping.
                                 self.consume_token();
                                 return Ok(LineEndType::LineBreak);
                             }
+                            ("", _) => return Ok(LineEndType::LineBreak),
                             other => other,
                         }
                     } else {
@@ -405,6 +555,33 @@ where
                     }
                 }
 
+                Token::InlineObject {
+                    plugin,
+                    index,
+                    size,
+                    alignment,
+                } => {
+                    if !self.empty
+                        && !self.plugin.continues_word()
+                        && self.split_word_wraps(handler, size.width)
+                    {
+                        return Ok(LineEndType::LineBreak);
+                    }
+
+                    if self.move_cursor_forward(size.width).is_err() {
+                        if !self.empty && !self.word_overflows(size.width) {
+                            return Ok(LineEndType::LineBreak);
+                        }
+
+                        // Objects can't be broken, so an object that is wider than the line is
+                        // cut off at the end of the line.
+                        let _ = self.move_cursor_forward(self.cursor.space());
+                    }
+
+                    self.empty = false;
+                    handler.inline_object(plugin, index, size, alignment)?;
+                }
+
                 Token::ChangeTextStyle(change) => handler.change_text_style(change)?,
 
                 Token::CarriageReturn => {
@@ -432,8 +609,11 @@ where
     ) -> Result<(), E::Error> {
         loop {
             let mut iter = w.char_indices();
-            match iter.find(|(_, c)| *c == SPEC_CHAR_NBSP) {
-                Some((space_pos, _)) => {
+            // Words may contain spaces that don't allow breaking, like the non-breaking space.
+            // Other non-breaking whitespace characters, like the narrow no-break space, are
+            // displayed using their own glyphs.
+            match iter.find(|(_, c)| [' ', SPEC_CHAR_NBSP].contains(c)) {
+                Some((space_pos, c)) => {
                     // If we have anything before the space...
                     if space_pos != 0 {
                         let word = unsafe {
@@ -443,7 +623,11 @@ where
                         handler.printed_characters(word, None)?;
                     }
 
-                    handler.whitespace("\u{a0}", 1, self.spaces.consume(1))?;
+                    let space = unsafe {
+                        // Safety: space_pos must be a character boundary
+                        w.get_unchecked(space_pos..space_pos + c.len_utf8())
+                    };
+                    handler.whitespace(space, 1, self.spaces.consume(1))?;
 
                     // If we have anything after the space...
                     w = iter.as_str();
@@ -519,7 +703,7 @@ pub(crate) mod test {
         }
     }
 
-    impl<'el, F: TextRenderer> ElementHandler for TestElementHandler<F> {
+    impl<F: TextRenderer> ElementHandler for TestElementHandler<F> {
         type Error = Infallible;
         type Color = F::Color;
 
@@ -532,8 +716,7 @@ pub(crate) mod test {
         }
 
         fn whitespace(&mut self, _string: &str, count: u32, width: u32) -> Result<(), Self::Error> {
-            self.elements
-                .push(RenderElement::Space(count, (width > 0) as bool));
+            self.elements.push(RenderElement::Space(count, width > 0));
             Ok(())
         }
 
@@ -707,6 +890,22 @@ pub(crate) mod test {
         );
     }
 
+    #[test]
+    #[cfg(feature = "grapheme")]
+    fn long_word_is_not_broken_inside_grapheme_cluster() {
+        let mut parser = Parser::parse("ae\u{301}b");
+        let mw = PluginWrapper::new(NoPlugin::<Rgb888>::new());
+
+        assert_line_elements(&mut parser, 2, &[RenderElement::string("a", 6)], &mw);
+        assert_line_elements(
+            &mut parser,
+            2,
+            &[RenderElement::string("e\u{301}", 12)],
+            &mw,
+        );
+        assert_line_elements(&mut parser, 2, &[RenderElement::string("b", 6)], &mw);
+    }
+
     #[test]
     fn soft_hyphen_issue_42() {
         let mut parser =
@@ -742,6 +941,26 @@ pub(crate) mod test {
         );
     }
 
+    #[test]
+    #[cfg(feature = "linebreak")]
+    fn narrow_nbsp_is_rendered_as_character() {
+        use crate::style::LineBreaking;
+
+        let mut parser = Parser::parse("1\u{202F}000 km").with_line_breaking(LineBreaking::Unicode);
+        let mw = PluginWrapper::new(NoPlugin::<Rgb888>::new());
+
+        assert_line_elements(
+            &mut parser,
+            50,
+            &[
+                RenderElement::string("1\u{202F}000", 30),
+                RenderElement::Space(1, true),
+                RenderElement::string("km", 12),
+            ],
+            &mw,
+        );
+    }
+
     #[test]
     fn tabs() {
         let mut parser = Parser::parse("a\tword\nand\t\tanother\t");
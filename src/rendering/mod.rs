@@ -1,21 +1,29 @@
 //! Pixel iterators used for text rendering.
 
+#[cfg(feature = "bidi")]
+mod bidi;
 pub(crate) mod cursor;
+pub(crate) mod fonts;
 pub(crate) mod line;
 pub(crate) mod line_iter;
 pub(crate) mod space_config;
+pub(crate) mod spacing;
 
 use crate::{
     parser::Parser,
     plugin::{PluginMarker as Plugin, ProcessingState},
     rendering::{
         cursor::Cursor,
-        line::{LineRenderState, StyledLineRenderer},
+        fonts::Fonts,
+        line::{fill_background, LineRenderState, StyledLineRenderer},
+        spacing::SpacedTextRenderer,
     },
-    style::TextBoxStyle,
+    style::{LineBreaking, LineMeasurement, TextBoxStyle},
+    utils::str_width,
     TextBox,
 };
 use az::SaturatingAs;
+use core::ops::Range;
 use embedded_graphics::{
     draw_target::{DrawTarget, DrawTargetExt},
     prelude::{Dimensions, Point, Size},
@@ -39,8 +47,14 @@ pub struct TextBoxProperties<'a, S> {
     /// The height of the text.
     pub text_height: i32,
 
+    /// The width of the widest line of the text.
+    pub text_width: u32,
+
     /// The bounds of the text box.
     pub bounding_box: Rectangle,
+
+    /// The area of the text box that contains the text, i.e. the bounds without the padding.
+    pub text_area: Rectangle,
 }
 
 impl<'a, F, M> Drawable for TextBox<'a, F, M>
@@ -57,24 +71,62 @@ where
         &self,
         display: &mut D,
     ) -> Result<&'a str, D::Error> {
+        let character_style = SpacedTextRenderer::new(
+            Fonts::new(&self.character_style, self.fonts, F::clone),
+            &self.style,
+        );
+        let text_area = self.insets().apply(self.bounds);
+
+        self.decoration.draw(self.bounds, display)?;
+
         let mut cursor = Cursor::new(
-            self.bounds,
-            self.character_style.line_height(),
+            text_area,
+            character_style.line_height(),
             self.style.line_height,
-            self.style.tab_size.into_pixels(&self.character_style),
+            self.style.tab_size.into_pixels(&character_style),
         );
 
-        let text_height = self
-            .style
-            .measure_text_height_impl(
-                self.plugin.clone(),
-                &self.character_style,
-                self.text,
-                cursor.line_width(),
+        let measurement = self.style.measure_text_impl(
+            self.plugin.clone(),
+            &self.character_style,
+            self.fonts,
+            self.text,
+            cursor.line_width(),
+        );
+        let text_height = measurement.height.saturating_as::<i32>();
+        let text_width = measurement.width;
+
+        // With tight bounds, the character cells of the first and last lines may reach outside of
+        // the bounding box.
+        let clip_area = display.bounding_box();
+        let clip_area = if measurement.trimmed_top > 0 || measurement.trimmed_bottom > 0 {
+            Rectangle::new(
+                Point::new(clip_area.top_left.x, self.bounds.top_left.y),
+                Size::new(clip_area.size.width, self.bounds.size.height),
             )
-            .saturating_as::<i32>();
+        } else {
+            clip_area
+        };
+        let display = &mut display.clipped(&clip_area);
 
-        let box_height = self.bounding_box().size.height.saturating_as::<i32>();
+        let box_height = text_area.size.height.saturating_as::<i32>();
+
+        let leading = if self.style.half_leading {
+            cursor.leading()
+        } else {
+            0
+        };
+        let leading_above = leading / 2;
+        cursor.y += leading_above.saturating_as::<i32>();
+
+        // The rows that are not part of the measured height may be outside of the text area.
+        cursor.y -= measurement.trimmed_top.saturating_as::<i32>();
+        cursor.extend(
+            measurement.trimmed_top.saturating_sub(leading_above),
+            measurement
+                .trimmed_bottom
+                .saturating_sub(leading - leading_above),
+        );
 
         self.style.vertical_alignment.apply_vertical_alignment(
             &mut cursor,
@@ -83,42 +135,65 @@ where
         );
 
         cursor.y += self.vertical_offset;
+        cursor.x_offset += self.horizontal_offset;
 
         let props = TextBoxProperties {
             box_style: &self.style,
-            char_style: &self.character_style,
+            char_style: &character_style,
             text_height,
+            text_width,
             bounding_box: self.bounding_box(),
+            text_area,
         };
 
         self.plugin.on_start_render(&mut cursor, props);
 
-        let mut state = LineRenderState {
-            text_renderer: self.character_style.clone(),
-            parser: Parser::parse(self.text),
-            end_type: LineEndType::EndOfText,
-            plugin: &self.plugin,
-        };
+        let mut state = LineRenderState::new(
+            Parser::parse(self.text).with_line_breaking(self.style.line_breaking),
+            character_style.clone(),
+            &self.plugin,
+        );
 
         state.plugin.set_state(ProcessingState::Render);
 
+        // Hanging punctuation is drawn outside of the bounding box.
+        let hanging_punctuation_width = if self.style.line_breaking == LineBreaking::Cjk {
+            str_width(&character_style, "\u{3002}")
+        } else {
+            0
+        };
+
+        self.fill_rows(
+            &character_style,
+            text_area,
+            cursor.y - leading_above.saturating_as::<i32>()..cursor.y,
+            display,
+        )?;
+
         let mut anything_drawn = false;
         loop {
             state.plugin.new_line();
 
+            if measurement.varying_line_heights {
+                cursor.set_line_height(self.measure_next_line(&state, &cursor).line_height);
+            }
+
             let display_range = self
                 .style
                 .height_mode
                 .calculate_displayed_row_range(&cursor);
             let display_range_start = display_range.start.saturating_as::<i32>();
             let display_range_count = display_range.count() as u32;
-            let display_size = Size::new(cursor.line_width(), display_range_count);
+            let display_size = Size::new(
+                cursor.line_width() + hanging_punctuation_width,
+                display_range_count,
+            );
 
             let line_start = cursor.line_start();
 
             // FIXME: cropping isn't necessary for whole lines, but make sure not to blow up the
             // binary size as well. We could also use a different way to consume invisible text.
-            let mut display = display.clipped(&Rectangle::new(
+            let mut line_display = display.clipped(&Rectangle::new(
                 line_start + Point::new(0, display_range_start),
                 display_size,
             ));
@@ -130,8 +205,8 @@ where
                     let consumed_bytes = self.text.len() - remaining_bytes;
 
                     state.plugin.post_render(
-                        &mut display,
-                        &self.character_style,
+                        &mut line_display,
+                        &character_style,
                         None,
                         Rectangle::new(line_start, Size::new(0, cursor.line_height())),
                     )?;
@@ -142,25 +217,59 @@ where
                 anything_drawn = true;
             }
 
-            StyledLineRenderer {
-                cursor: cursor.line(),
-                state: &mut state,
-                style: &self.style,
+            if let Some(elided_text) = measurement.elided_text {
+                StyledLineRenderer {
+                    cursor: cursor.line(),
+                    state: &mut state,
+                    style: &self.style,
+                    ellipsis: None,
+                }
+                .draw_elided(&mut line_display, elided_text)?;
+            } else {
+                let ellipsis =
+                    self.style.overflow.ellipsis().filter(|_| {
+                        display_range_count > 0 && self.is_cut_off_after(&state, &cursor)
+                    });
+
+                StyledLineRenderer {
+                    cursor: cursor.line(),
+                    state: &mut state,
+                    style: &self.style,
+                    ellipsis,
+                }
+                .draw(&mut line_display)?;
             }
-            .draw(&mut display)?;
 
             match state.end_type {
                 LineEndType::EndOfText => {
+                    let line_bottom = cursor.y + cursor.line_height().saturating_as::<i32>();
+                    let leading_below = (leading - leading_above).saturating_as::<i32>();
+                    self.fill_rows(
+                        &state.text_renderer,
+                        text_area,
+                        line_bottom..line_bottom + leading_below,
+                        display,
+                    )?;
+
                     state.plugin.on_rendering_finished();
                     break;
                 }
                 LineEndType::CarriageReturn => {}
                 _ => {
+                    let line_bottom = cursor.y + cursor.line_height().saturating_as::<i32>();
+
                     cursor.new_line();
 
                     if state.end_type == LineEndType::NewLine {
                         cursor.y += self.style.paragraph_spacing.saturating_as::<i32>();
                     }
+
+                    self.fill_rows(
+                        &state.text_renderer,
+                        text_area,
+                        line_bottom..cursor.y,
+                        display,
+                    )?;
                 }
             }
         }
@@ -169,6 +278,102 @@ where
     }
 }
 
+impl<'a, F, M> TextBox<'a, F, M>
+where
+    F: TextRenderer<Color = <F as CharacterStyle>::Color> + CharacterStyle,
+    M: Plugin<'a, <F as TextRenderer>::Color> + Plugin<'a, <F as CharacterStyle>::Color>,
+    <F as CharacterStyle>::Color: Default,
+{
+    /// Fills the given rows of the text area with the background color, if enabled.
+    fn fill_rows<S, D>(
+        &self,
+        text_renderer: &S,
+        text_area: Rectangle,
+        rows: Range<i32>,
+        display: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        S: TextRenderer,
+        D: DrawTarget<Color = S::Color>,
+    {
+        if !self.style.fill_line_background {
+            return Ok(());
+        }
+
+        let area = Rectangle::new(
+            Point::new(text_area.top_left.x, rows.start),
+            Size::new(
+                text_area.size.width,
+                (rows.end - rows.start).saturating_as::<u32>(),
+            ),
+        );
+
+        fill_background(text_renderer, area.intersection(&text_area), display)
+    }
+
+    /// Measures the line that is drawn next, without consuming it.
+    fn measure_next_line(
+        &self,
+        state: &LineRenderState<'a, '_, SpacedTextRenderer<Fonts<'_, F, F>>, M>,
+        cursor: &Cursor,
+    ) -> LineMeasurement {
+        let mut cloned_parser = state.parser.clone();
+        let measure_plugin = state.plugin.clone();
+        measure_plugin.set_state(ProcessingState::Measure);
+        self.style.measure_paragraph_line(
+            &mut state.line_widths.clone(),
+            &measure_plugin,
+            &mut state.text_renderer.clone(),
+            &mut cloned_parser,
+            cursor.line_width().saturating_sub(self.style.indent(
+                state.plugin,
+                &state.text_renderer,
+                state.first_line,
+                state.wrap_indent,
+            )),
+        )
+    }
+
+    /// Returns whether the text continues after the current line, but the next line is not
+    /// displayed.
+    fn is_cut_off_after(
+        &self,
+        state: &LineRenderState<'a, '_, SpacedTextRenderer<Fonts<'_, F, F>>, M>,
+        cursor: &Cursor,
+    ) -> bool {
+        // Only the last visible line needs to be measured: if the next line is displayed even
+        // after a paragraph break, the text is not cut off after the current line.
+        let mut next_line = *cursor;
+        next_line.new_line();
+        next_line.y += self.style.paragraph_spacing.saturating_as::<i32>();
+        if !self
+            .style
+            .height_mode
+            .calculate_displayed_row_range(&next_line)
+            .is_empty()
+        {
+            return false;
+        }
+
+        let lm = self.measure_next_line(state, cursor);
+
+        let mut next_line = *cursor;
+        match lm.line_end_type {
+            LineEndType::EndOfText | LineEndType::CarriageReturn => return false,
+            LineEndType::LineBreak => next_line.new_line(),
+            LineEndType::NewLine => {
+                next_line.new_line();
+                next_line.y += self.style.paragraph_spacing.saturating_as::<i32>();
+            }
+        }
+
+        self.style
+            .height_mode
+            .calculate_displayed_row_range(&next_line)
+            .is_empty()
+    }
+}
+
 #[cfg(test)]
 pub mod test {
     use embedded_graphics::{
@@ -180,12 +385,16 @@ pub mod test {
         pixelcolor::BinaryColor,
         prelude::*,
         primitives::Rectangle,
+        text::LineHeight,
     };
 
     use crate::{
-        alignment::HorizontalAlignment,
-        style::{HeightMode, TextBoxStyle, TextBoxStyleBuilder, VerticalOverdraw},
-        utils::test::{size_for, TestFont},
+        alignment::{HorizontalAlignment, VerticalAlignment},
+        style::{
+            BoxDecoration, HeightMode, Indent, Padding, TextBoxStyle, TextBoxStyleBuilder,
+            VerticalOverdraw,
+        },
+        utils::test::{size_for, InkTestFont, TestFont},
         TextBox,
     };
 
@@ -594,4 +803,606 @@ pub mod test {
             "......      ",
         ]);
     }
+
+    #[test]
+    #[cfg(feature = "linebreak")]
+    fn unicode_line_breaking_wraps_after_hyphen() {
+        use crate::style::LineBreaking;
+
+        assert_styled_rendered(
+            TextBoxStyle::with_line_breaking(LineBreaking::Unicode),
+            "a ab-cd",
+            size_for(&FONT_6X9, 5, 2),
+            &[
+                "..............................",
+                "...................#..........",
+                "...................#..........",
+                "..###.........###..###........",
+                ".#..#........#..#..#..#.#####.",
+                ".#..#........#..#..#..#.......",
+                "..###.........###..###........",
+                "..............................",
+                "..............................",
+                "............                  ",
+                "..........#.                  ",
+                "..........#.                  ",
+                "..###...###.                  ",
+                ".#.....#..#.                  ",
+                ".#.....#..#.                  ",
+                "..###...###.                  ",
+                "............                  ",
+                "............                  ",
+            ],
+        );
+    }
+
+    #[test]
+    fn clipped_word_is_cut_off_at_the_edge() {
+        use crate::style::WordBreak;
+
+        assert_styled_rendered(
+            TextBoxStyle::with_word_break(WordBreak::Clip),
+            "ab abcdefgh",
+            size_for(&FONT_6X9, 5, 1) - Size::new(3, 0),
+            &[
+                "...........................",
+                ".......#.................#.",
+                ".......#.................#.",
+                "..###..###..........###..##",
+                ".#..#..#..#........#..#..#.",
+                ".#..#..#..#........#..#..#.",
+                "..###..###..........###..##",
+                "...........................",
+                "...........................",
+            ],
+        );
+    }
+
+    #[test]
+    fn ellipsis_replaces_end_of_last_visible_line() {
+        use crate::style::TextOverflow;
+
+        assert_styled_rendered(
+            TextBoxStyle::with_overflow(TextOverflow::Ellipsis("..")),
+            "ab cd ef gh ij",
+            size_for(&FONT_6X9, 6, 2),
+            &[
+                "..............................",
+                ".......#....................#.",
+                ".......#....................#.",
+                "..###..###..........###...###.",
+                ".#..#..#..#........#.....#..#.",
+                ".#..#..#..#........#.....#..#.",
+                "..###..###..........###...###.",
+                "..............................",
+                "..............................",
+                "........................      ",
+                ".........#..............      ",
+                "........#.#.............      ",
+                "..##....#...............      ",
+                ".#.##..###..............      ",
+                ".##.....#.....##....##..      ",
+                "..###...#.....##....##..      ",
+                "........................      ",
+                "........................      ",
+            ],
+        );
+    }
+
+    #[test]
+    fn middle_ellipsis_shortens_single_line_text() {
+        use crate::style::TextOverflow;
+
+        assert_styled_rendered(
+            TextBoxStyle::with_overflow(TextOverflow::MiddleEllipsis("..")),
+            "abcdefghij",
+            size_for(&FONT_6X9, 6, 2),
+            &[
+                "....................................",
+                ".......#..................#......#..",
+                ".......#............................",
+                "..###..###...............##.....##..",
+                ".#..#..#..#...............#......#..",
+                ".#..#..#..#...##....##....#......#..",
+                "..###..###....##....##...###.....#..",
+                "...............................#.#..",
+                "................................#...",
+            ],
+        );
+    }
+
+    #[test]
+    fn no_wrap_only_breaks_at_newline() {
+        use crate::style::LineBreaking;
+
+        assert_styled_rendered(
+            TextBoxStyle::with_line_breaking(LineBreaking::NoWrap),
+            "ab cd\nef",
+            size_for(&FONT_6X9, 4, 2),
+            &[
+                "........................",
+                ".......#................",
+                ".......#................",
+                "..###..###..........###.",
+                ".#..#..#..#........#....",
+                ".#..#..#..#........#....",
+                "..###..###..........###.",
+                "........................",
+                "........................",
+                "............            ",
+                ".........#..            ",
+                "........#.#.            ",
+                "..##....#...            ",
+                ".#.##..###..            ",
+                ".##.....#...            ",
+                "..###...#...            ",
+                "............            ",
+                "............            ",
+            ],
+        );
+    }
+
+    #[test]
+    fn horizontal_offset() {
+        use crate::style::LineBreaking;
+
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        TextBox::with_textbox_style(
+            "hello world",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 5, 1)),
+            character_style,
+            TextBoxStyle::with_line_breaking(LineBreaking::NoWrap),
+        )
+        .set_horizontal_offset(-6 * 6)
+        .draw(&mut display)
+        .unwrap();
+
+        display.assert_pattern(&[
+            "..............................",
+            "...................##.......#.",
+            "....................#.......#.",
+            "#...#...##...#.#....#.....###.",
+            "#.#.#..#..#..##.#...#....#..#.",
+            "#.#.#..#..#..#......#....#..#.",
+            ".#.#....##...#.....###....###.",
+            "..............................",
+            "..............................",
+        ]);
+    }
+
+    #[test]
+    fn hanging_punctuation_is_drawn_outside_of_the_text_box() {
+        use crate::style::LineBreaking;
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let mut display = MockDisplay::new();
+        TextBox::with_textbox_style(
+            "ab\u{3002}",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 2, 1)),
+            character_style,
+            TextBoxStyle::with_line_breaking(LineBreaking::Cjk),
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        // The font doesn't contain the ideographic full stop.
+        let mut expected = MockDisplay::new();
+        TextBox::new(
+            "ab?",
+            Rectangle::new(Point::zero(), size_for(&FONT_6X9, 3, 1)),
+            character_style,
+        )
+        .draw(&mut expected)
+        .unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    #[cfg(feature = "bidi")]
+    fn right_to_left_paragraph_is_reordered() {
+        use crate::{
+            alignment::HorizontalAlignment,
+            style::{TextBoxStyleBuilder, TextDirection},
+        };
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+        let bounds = Rectangle::new(Point::zero(), size_for(&FONT_6X9, 8, 2));
+
+        let mut display = MockDisplay::new();
+        TextBox::with_textbox_style(
+            "ab (c)!\n12",
+            bounds,
+            character_style,
+            TextBoxStyleBuilder::new()
+                .alignment(HorizontalAlignment::Start)
+                .direction(TextDirection::RightToLeft)
+                .build(),
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        // Trailing neutral characters are displayed on the left side of the line, mirrored.
+        let mut expected = MockDisplay::new();
+        TextBox::with_alignment(
+            "!(ab (c\n12",
+            bounds,
+            character_style,
+            HorizontalAlignment::Right,
+        )
+        .draw(&mut expected)
+        .unwrap();
+
+        display.assert_eq(&expected);
+    }
+
+    #[test]
+    fn letter_spacing_is_used_for_alignment() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .alignment(HorizontalAlignment::Right)
+                .letter_spacing(1)
+                .build(),
+            "ab",
+            size_for(&FONT_6X9, 4, 1),
+            &[
+                "          ..............",
+                "          ........#.....",
+                "          ........#.....",
+                "          ..###...###...",
+                "          .#..#...#..#..",
+                "          .#..#...#..#..",
+                "          ..###...###...",
+                "          ..............",
+                "          ..............",
+            ],
+        );
+    }
+
+    #[test]
+    fn word_spacing_widens_spaces() {
+        assert_styled_rendered(
+            TextBoxStyle::with_word_spacing(3),
+            "a b c",
+            size_for(&FONT_6X9, 5, 2),
+            &[
+                ".....................",
+                "................#....",
+                "................#....",
+                "..###...........###..",
+                ".#..#...........#..#.",
+                ".#..#...........#..#.",
+                "..###...........###..",
+                ".....................",
+                ".....................",
+                "......               ",
+                "......               ",
+                "......               ",
+                "..###.               ",
+                ".#....               ",
+                ".#....               ",
+                "..###.               ",
+                "......               ",
+                "......               ",
+            ],
+        );
+    }
+
+    #[test]
+    fn first_line_and_hanging_indent() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .first_line_indent(Indent::Spaces(2))
+                .hanging_indent(Indent::Pixels(6))
+                .build(),
+            "abc def\ngh",
+            size_for(&FONT_6X9, 6, 3),
+            &[
+                "            ..................",
+                "            .......#..........",
+                "            .......#..........",
+                "            ..###..###....###.",
+                "            .#..#..#..#..#....",
+                "            .#..#..#..#..#....",
+                "            ..###..###....###.",
+                "            ..................",
+                "            ..................",
+                "      ..................      ",
+                "      ....#..........#..      ",
+                "      ....#.........#.#.      ",
+                "      ..###...##....#...      ",
+                "      .#..#..#.##..###..      ",
+                "      .#..#..##.....#...      ",
+                "      ..###...###...#...      ",
+                "      ..................      ",
+                "      ..................      ",
+                "            ............      ",
+                "            .......#....      ",
+                "            .......#....      ",
+                "            ..##...###..      ",
+                "            .#..#..#..#.      ",
+                "            .#..#..#..#.      ",
+                "            ..###..#..#.      ",
+                "            ....#.......      ",
+                "            ..##........      ",
+            ],
+        );
+    }
+
+    #[test]
+    fn wrapped_lines_preserve_indentation() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .preserve_indentation(true)
+                .build(),
+            " ab cd",
+            size_for(&FONT_6X9, 3, 2),
+            &[
+                "..................",
+                ".............#....",
+                ".............#....",
+                "........###..###..",
+                ".......#..#..#..#.",
+                ".......#..#..#..#.",
+                "........###..###..",
+                "..................",
+                "..................",
+                "      ............",
+                "      ..........#.",
+                "      ..........#.",
+                "      ..###...###.",
+                "      .#.....#..#.",
+                "      .#.....#..#.",
+                "      ..###...###.",
+                "      ............",
+                "      ............",
+            ],
+        );
+    }
+
+    #[test]
+    fn padding_shrinks_text_area() {
+        let mut display = MockDisplay::new();
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .background_color(BinaryColor::Off)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "ab cd",
+            Rectangle::new(Point::zero(), Size::new(22, 0)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .padding(Padding::new(1, 2, 3, 6))
+                .build(),
+        );
+
+        assert_eq!(text_box.bounding_box().size, Size::new(22, 22));
+
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "                  ",
+            "      ............",
+            "      .......#....",
+            "      .......#....",
+            "      ..###..###..",
+            "      .#..#..#..#.",
+            "      .#..#..#..#.",
+            "      ..###..###..",
+            "      ............",
+            "      ............",
+            "      ............",
+            "      ..........#.",
+            "      ..........#.",
+            "      ..###...###.",
+            "      .#.....#..#.",
+            "      .#.....#..#.",
+            "      ..###...###.",
+            "      ............",
+            "      ............",
+        ]);
+    }
+
+    #[test]
+    fn decoration_is_drawn_behind_text() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let text_box = TextBox::with_textbox_style(
+            "ab",
+            Rectangle::new(Point::zero(), Size::new(16, 0)),
+            character_style,
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .padding(Padding::new(0, 1, 0, 1))
+                .build(),
+        )
+        .with_decoration(
+            BoxDecoration::new()
+                .with_background(BinaryColor::Off)
+                .with_border(BinaryColor::On, 1)
+                .with_corner_radius(2),
+        );
+
+        assert_eq!(text_box.bounding_box().size, Size::new(16, 11));
+
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            " ############## ",
+            "#..............#",
+            "#........#.....#",
+            "#........#.....#",
+            "#...###..###...#",
+            "#..#..#..#..#..#",
+            "#..#..#..#..#..#",
+            "#...###..###...#",
+            "#..............#",
+            "#..............#",
+            " ############## ",
+        ]);
+    }
+
+    #[test]
+    fn fill_line_background() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .alignment(HorizontalAlignment::Center)
+                .line_height(LineHeight::Pixels(10))
+                .paragraph_spacing(2)
+                .fill_line_background(true)
+                .build(),
+            "ab cd\nef",
+            size_for(&FONT_6X9, 4, 3),
+            &[
+                "........................",
+                ".............#..........",
+                ".............#..........",
+                "........###..###........",
+                ".......#..#..#..#.......",
+                ".......#..#..#..#.......",
+                "........###..###........",
+                "........................",
+                "........................",
+                "........................",
+                "........................",
+                "................#.......",
+                "................#.......",
+                "........###...###.......",
+                ".......#.....#..#.......",
+                ".......#.....#..#.......",
+                "........###...###.......",
+                "........................",
+                "........................",
+                "........................",
+                "........................",
+                "........................",
+            ],
+        );
+    }
+
+    #[test]
+    fn half_leading() {
+        assert_styled_rendered(
+            TextBoxStyleBuilder::new()
+                .line_height(LineHeight::Pixels(13))
+                .half_leading(true)
+                .fill_line_background(true)
+                .build(),
+            "ab\ncd",
+            Size::new(12, 26),
+            &[
+                "............",
+                "............",
+                "............",
+                ".......#....",
+                ".......#....",
+                "..###..###..",
+                ".#..#..#..#.",
+                ".#..#..#..#.",
+                "..###..###..",
+                "............",
+                "............",
+                "............",
+                "............",
+                "............",
+                "............",
+                "............",
+                "..........#.",
+                "..........#.",
+                "..###...###.",
+                ".#.....#..#.",
+                ".#.....#..#.",
+                "..###...###.",
+                "............",
+                "............",
+                "............",
+                "............",
+            ],
+        );
+    }
+
+    #[test]
+    fn tight_bounds_are_centered() {
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+
+        let text_box = TextBox::with_textbox_style(
+            "00",
+            Rectangle::new(Point::zero(), Size::new(10, 13)),
+            InkTestFont(TestFont::new(BinaryColor::On, BinaryColor::Off)),
+            TextBoxStyleBuilder::new()
+                .vertical_alignment(VerticalAlignment::Middle)
+                .tight_bounds(true)
+                .build(),
+        );
+
+        text_box.draw(&mut display).unwrap();
+
+        display.assert_pattern(&[
+            "         ",
+            "         ",
+            "         ",
+            "####.####",
+            "#..#.#..#",
+            "#..#.#..#",
+            "#..#.#..#",
+            "#..#.#..#",
+            "#..#.#..#",
+            "####.####",
+            ".........",
+            ".........",
+            ".........",
+        ]);
+    }
+
+    #[test]
+    fn fit_to_tight_bounds() {
+        let text_box = TextBox::with_textbox_style(
+            "00 00",
+            Rectangle::new(Point::zero(), Size::new(10, 0)),
+            InkTestFont(TestFont::new(BinaryColor::On, BinaryColor::Off)),
+            TextBoxStyleBuilder::new()
+                .height_mode(HeightMode::FitToText)
+                .tight_bounds(true)
+                .build(),
+        );
+
+        assert_eq!(text_box.bounding_box().size, Size::new(10, 17));
+
+        // The character cells are taller than the text, but are not drawn outside of the box.
+        let mut display = MockDisplay::new();
+        display.set_allow_overdraw(true);
+        text_box.draw(&mut display).unwrap();
+
+        assert_eq!(
+            display.affected_area(),
+            Rectangle::new(Point::zero(), Size::new(9, 17))
+        );
+    }
 }
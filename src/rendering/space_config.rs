@@ -25,6 +25,11 @@ impl SpaceConfig {
         }
     }
 
+    /// Returns whether the spaces are stretched to justify the line.
+    pub const fn is_justified(&self) -> bool {
+        self.count.is_some()
+    }
+
     /// Advance the internal state
     pub fn consume(&mut self, n: u32) -> u32 {
         let w = self.peek_next_width(n);
@@ -36,3 +41,50 @@ impl SpaceConfig {
         w
     }
 }
+
+/// Extra space inserted between the glyphs of a line justified by distributing characters.
+#[derive(Copy, Clone, Debug)]
+pub struct GlyphSpacing {
+    /// The width of the gaps.
+    gaps: SpaceConfig,
+
+    /// The number of gaps left in the line. This field changes during rendering.
+    remaining: u32,
+}
+
+impl GlyphSpacing {
+    /// Creates a new GlyphSpacing object that doesn't insert space between glyphs.
+    pub const fn none() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    /// Creates a new GlyphSpacing object.
+    ///
+    /// The first `extra_pixels` gaps are one pixel wider than `width`.
+    pub const fn new(width: u32, extra_pixels: u32, count: u32) -> Self {
+        Self {
+            gaps: SpaceConfig::new(width, Some(extra_pixels)),
+            remaining: count,
+        }
+    }
+
+    /// Returns whether there is any space left to be inserted.
+    pub const fn is_none(&self) -> bool {
+        self.remaining == 0
+    }
+
+    /// Returns the total width of the remaining gaps.
+    pub fn width(&self) -> u32 {
+        self.gaps.peek_next_width(self.remaining)
+    }
+
+    /// Returns the width of the gap after the next glyph.
+    pub fn consume(&mut self) -> u32 {
+        if self.remaining == 0 {
+            return 0;
+        }
+
+        self.remaining -= 1;
+        self.gaps.consume(1)
+    }
+}
@@ -0,0 +1,224 @@
+//! Letter and word spacing.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    prelude::Point,
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+};
+
+use crate::{
+    alignment::BaselineShift,
+    rendering::fonts::SelectFont,
+    style::TextBoxStyle,
+    utils::{graphemes, str_width},
+};
+
+/// Text renderer that adds letter and word spacing to another text renderer.
+///
+/// Every measurement and drawing operation goes through the text renderer, so wrapping it keeps
+/// line breaking, alignment and rendering consistent.
+#[derive(Clone, Debug)]
+pub(crate) struct SpacedTextRenderer<S> {
+    inner: S,
+    letter_spacing: i32,
+    word_spacing: i32,
+}
+
+impl<S> SpacedTextRenderer<S> {
+    /// Wraps a text renderer using the spacing options of the text box style.
+    pub const fn new(inner: S, style: &TextBoxStyle) -> Self {
+        Self {
+            inner,
+            letter_spacing: style.letter_spacing,
+            word_spacing: style.word_spacing,
+        }
+    }
+
+    const fn is_spaced(&self) -> bool {
+        self.letter_spacing != 0 || self.word_spacing != 0
+    }
+
+    /// Returns the space added after a grapheme cluster.
+    fn spacing(&self, grapheme: &str) -> i32 {
+        if grapheme == " " {
+            self.letter_spacing + self.word_spacing
+        } else {
+            self.letter_spacing
+        }
+    }
+}
+
+impl<S: TextRenderer> SpacedTextRenderer<S> {
+    /// Returns the distance between the start of a grapheme cluster and the next one.
+    fn advance(&self, grapheme: &str) -> u32 {
+        (str_width(&self.inner, grapheme) as i32 + self.spacing(grapheme)).max(0) as u32
+    }
+}
+
+impl<S: TextRenderer> TextRenderer for SpacedTextRenderer<S> {
+    type Color = S::Color;
+
+    #[inline]
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        if !self.is_spaced() {
+            return self.inner.draw_string(text, position, baseline, target);
+        }
+
+        let mut position = position;
+        for (_, grapheme) in graphemes(text) {
+            let next = self
+                .inner
+                .draw_string(grapheme, position, baseline, target)?;
+
+            let spacing = self.spacing(grapheme);
+            if spacing > 0 {
+                self.inner
+                    .draw_whitespace(spacing as u32, next, baseline, target)?;
+            }
+
+            position.x += self.advance(grapheme) as i32;
+        }
+
+        Ok(position)
+    }
+
+    #[inline]
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.inner
+            .draw_whitespace(width, position, baseline, target)
+    }
+
+    #[inline]
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let mut metrics = self.inner.measure_string(text, position, baseline);
+        if !self.is_spaced() {
+            return metrics;
+        }
+
+        let natural_width = metrics.next_position.x - position.x;
+        let width = graphemes(text)
+            .map(|(_, grapheme)| self.advance(grapheme))
+            .sum::<u32>() as i32;
+
+        let bounding_box_width = metrics.bounding_box.size.width as i32 + width - natural_width;
+        metrics.bounding_box.size.width = bounding_box_width.max(0) as u32;
+        metrics.next_position.x = position.x + width;
+
+        metrics
+    }
+
+    #[inline]
+    fn line_height(&self) -> u32 {
+        self.inner.line_height()
+    }
+}
+
+impl<S: CharacterStyle> CharacterStyle for SpacedTextRenderer<S> {
+    type Color = S::Color;
+
+    #[inline]
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.inner.set_text_color(text_color);
+    }
+
+    #[inline]
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.inner.set_background_color(background_color);
+    }
+
+    #[inline]
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.inner.set_underline_color(underline_color);
+    }
+
+    #[inline]
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.inner.set_strikethrough_color(strikethrough_color);
+    }
+}
+
+impl<S: SelectFont> SelectFont for SpacedTextRenderer<S> {
+    #[inline]
+    fn select_font(&mut self, font: Option<usize>) {
+        self.inner.select_font(font);
+    }
+
+    #[inline]
+    fn shift_baseline(&mut self, shift: BaselineShift) {
+        self.inner.shift_baseline(shift);
+    }
+
+    #[inline]
+    fn baseline_shift(&self) -> i32 {
+        self.inner.baseline_shift()
+    }
+}
+
+/// Text renderer that borrows another one.
+///
+/// Used to wrap a text renderer that can't be cloned.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct Borrowed<'a, S>(pub &'a S);
+
+impl<S: TextRenderer> TextRenderer for Borrowed<'_, S> {
+    type Color = S::Color;
+
+    #[inline]
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.0.draw_string(text, position, baseline, target)
+    }
+
+    #[inline]
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.0.draw_whitespace(width, position, baseline, target)
+    }
+
+    #[inline]
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        self.0.measure_string(text, position, baseline)
+    }
+
+    #[inline]
+    fn line_height(&self) -> u32 {
+        self.0.line_height()
+    }
+}
@@ -2,8 +2,11 @@
 use embedded_graphics::text::LineHeight;
 
 use crate::{
-    alignment::{HorizontalAlignment, VerticalAlignment},
-    style::{HeightMode, TabSize, TextBoxStyle, VerticalOverdraw},
+    alignment::{HorizontalAlignment, Justification, VerticalAlignment},
+    style::{
+        HeightMode, Indent, LineBreaking, Padding, ParagraphBreaking, TabSize, TextBoxStyle,
+        TextDirection, TextOverflow, VerticalOverdraw, WordBreak,
+    },
 };
 
 /// [`TextBoxStyle`] builder object.
@@ -28,14 +31,31 @@ impl TextBoxStyleBuilder {
         Self {
             style: TextBoxStyle {
                 alignment: HorizontalAlignment::Left,
+                last_line_alignment: None,
+                justification: Justification::InterWord,
+                max_word_spacing: None,
                 vertical_alignment: VerticalAlignment::Top,
                 height_mode: HeightMode::Exact(VerticalOverdraw::FullRowsOnly),
+                padding: Padding::zero(),
+                fill_line_background: false,
                 line_height: LineHeight::Percent(100),
+                half_leading: false,
+                tight_bounds: false,
                 paragraph_spacing: 0,
+                letter_spacing: 0,
+                word_spacing: 0,
                 tab_size: TabSize::Spaces(4),
+                first_line_indent: Indent::Pixels(0),
+                hanging_indent: Indent::Pixels(0),
+                preserve_indentation: false,
                 // we will update these at build time
                 leading_spaces: false,
                 trailing_spaces: false,
+                line_breaking: LineBreaking::Simple,
+                paragraph_breaking: ParagraphBreaking::Greedy,
+                word_break: WordBreak::BreakWord,
+                overflow: TextOverflow::Clip,
+                direction: TextDirection::LeftToRight,
             },
             leading_spaces: None,
             trailing_spaces: None,
@@ -64,6 +84,49 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Distribute the leading evenly above and below the glyphs of each line.
+    ///
+    /// By default, glyphs are drawn at the top of their line and the space that the line height
+    /// adds is left below them. With half-leading, the glyphs are vertically centered in their
+    /// line, and the height of the text includes the space above the first and below the last
+    /// line.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// # use embedded_graphics::text::LineHeight;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .line_height(LineHeight::Percent(150))
+    ///     .half_leading(true)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn half_leading(mut self, half_leading: bool) -> Self {
+        self.style.half_leading = half_leading;
+
+        self
+    }
+
+    /// Measure the height of the text using the bounding boxes of its glyphs.
+    ///
+    /// By default, every line is as tall as the line height of the font, which includes space for
+    /// ascenders and descenders. With tight bounds, the height of the text spans from the top of
+    /// the highest glyph of the first line to the bottom of the lowest glyph of the last line.
+    /// This height is used by the height modes and the vertical alignment, so a single word can
+    /// be centered exactly. The parts of the character cells that reach outside of the bounding
+    /// box of the text box are not drawn.
+    ///
+    /// The bounding boxes are returned by the text renderer, which may not measure the glyphs
+    /// tightly. Monospace fonts, for example, always return the full character cell.
+    #[inline]
+    pub const fn tight_bounds(mut self, tight_bounds: bool) -> Self {
+        self.style.tight_bounds = tight_bounds;
+
+        self
+    }
+
     /// Sets the paragraph spacing.
     ///
     /// # Example
@@ -83,6 +146,38 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Sets the letter spacing.
+    ///
+    /// The letter spacing is added after every character, in pixels. Negative values move the
+    /// characters closer together.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::TextBoxStyleBuilder;
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .letter_spacing(1)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn letter_spacing(mut self, letter_spacing: i32) -> Self {
+        self.style.letter_spacing = letter_spacing;
+
+        self
+    }
+
+    /// Sets the word spacing.
+    ///
+    /// The word spacing is added to the width of every space, in pixels, in addition to the
+    /// letter spacing. Negative values move the words closer together.
+    #[inline]
+    pub const fn word_spacing(mut self, word_spacing: i32) -> Self {
+        self.style.word_spacing = word_spacing;
+
+        self
+    }
+
     /// Sets the horizontal text alignment.
     #[inline]
     pub const fn alignment(mut self, alignment: HorizontalAlignment) -> TextBoxStyleBuilder {
@@ -91,6 +186,48 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Sets the horizontal alignment of the last line of each paragraph.
+    ///
+    /// By default, the last line uses the same alignment as the other lines, except for justified
+    /// text, where it is aligned to the start of the paragraph.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::{alignment::HorizontalAlignment, style::TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .alignment(HorizontalAlignment::Justified)
+    ///     .last_line_alignment(HorizontalAlignment::Center)
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn last_line_alignment(mut self, alignment: HorizontalAlignment) -> Self {
+        self.style.last_line_alignment = Some(alignment);
+
+        self
+    }
+
+    /// Sets how justified lines are stretched.
+    #[inline]
+    pub const fn justification(mut self, justification: Justification) -> Self {
+        self.style.justification = justification;
+
+        self
+    }
+
+    /// Sets the maximum width of a space in justified lines, in pixels.
+    ///
+    /// Lines that would need wider spaces are not justified, unless
+    /// [`Justification::InterCharacter`] is used, in which case the rest of the space is
+    /// distributed between characters.
+    #[inline]
+    pub const fn max_word_spacing(mut self, max_word_spacing: u32) -> Self {
+        self.style.max_word_spacing = Some(max_word_spacing);
+
+        self
+    }
+
     /// Sets the vertical text alignment.
     #[inline]
     pub const fn vertical_alignment(
@@ -110,6 +247,42 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Sets the padding.
+    ///
+    /// The text is laid out inside the bounding box, shrunk by the padding. The bounding box of the
+    /// text box is not changed. [`HeightMode::FitToText`] and [`HeightMode::ShrinkToText`]
+    /// include the vertical padding in the height of the text box.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{Padding, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .padding(Padding::symmetric(2, 4))
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn padding(mut self, padding: Padding) -> TextBoxStyleBuilder {
+        self.style.padding = padding;
+
+        self
+    }
+
+    /// Fill the whole area of the lines with the background color of the character style.
+    ///
+    /// By default, only the glyphs and the spaces between them are drawn with a background. If
+    /// this option is set, the space around the text of each line, the line spacing and the
+    /// paragraph spacing are also filled, so text can be redrawn without clearing the display
+    /// first. The space before the text uses the background color at the start of the line, the
+    /// rest uses the background color at the end of the line.
+    #[inline]
+    pub const fn fill_line_background(mut self, fill: bool) -> TextBoxStyleBuilder {
+        self.style.fill_line_background = fill;
+
+        self
+    }
+
     /// Sets the tab size.
     #[inline]
     pub const fn tab_size(mut self, tab_size: TabSize) -> Self {
@@ -118,6 +291,118 @@ impl TextBoxStyleBuilder {
         self
     }
 
+    /// Sets the indentation of the first line of each paragraph.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{Indent, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .first_line_indent(Indent::Spaces(2))
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn first_line_indent(mut self, indent: Indent) -> Self {
+        self.style.first_line_indent = indent;
+
+        self
+    }
+
+    /// Sets the indentation of every line of each paragraph except the first one.
+    ///
+    /// A hanging indent can be used to align wrapped lines of a list item with the text after the
+    /// bullet.
+    #[inline]
+    pub const fn hanging_indent(mut self, indent: Indent) -> Self {
+        self.style.hanging_indent = indent;
+
+        self
+    }
+
+    /// Indent wrapped lines to match the leading whitespace of their paragraph.
+    ///
+    /// This is similar to the soft wrapping of code editors. The hanging indent is added to the
+    /// preserved indentation. Leading spaces must be rendered for this option to have an effect.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{Indent, TextBoxStyleBuilder};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .preserve_indentation(true)
+    ///     .hanging_indent(Indent::Spaces(2))
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn preserve_indentation(mut self, preserve: bool) -> Self {
+        self.style.preserve_indentation = preserve;
+
+        self
+    }
+
+    /// Sets the line breaking mode.
+    #[inline]
+    pub const fn line_breaking(mut self, line_breaking: LineBreaking) -> Self {
+        self.style.line_breaking = line_breaking;
+
+        self
+    }
+
+    /// Sets the paragraph breaking strategy.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::{alignment::HorizontalAlignment, style::{ParagraphBreaking, TextBoxStyleBuilder}};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .alignment(HorizontalAlignment::Justified)
+    ///     .paragraph_breaking(ParagraphBreaking::TotalFit { max_breakpoints: 200 })
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn paragraph_breaking(mut self, paragraph_breaking: ParagraphBreaking) -> Self {
+        self.style.paragraph_breaking = paragraph_breaking;
+
+        self
+    }
+
+    /// Sets the word breaking mode.
+    #[inline]
+    pub const fn word_break(mut self, word_break: WordBreak) -> Self {
+        self.style.word_break = word_break;
+
+        self
+    }
+
+    /// Sets the text overflow mode.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use embedded_text::style::{TextBoxStyleBuilder, TextOverflow};
+    /// #
+    /// let style = TextBoxStyleBuilder::new()
+    ///     .overflow(TextOverflow::Ellipsis("..."))
+    ///     .build();
+    /// ```
+    #[inline]
+    pub const fn overflow(mut self, overflow: TextOverflow) -> Self {
+        self.style.overflow = overflow;
+
+        self
+    }
+
+    /// Sets the text direction.
+    #[inline]
+    pub const fn direction(mut self, direction: TextDirection) -> Self {
+        self.style.direction = direction;
+
+        self
+    }
+
     /// Render leading spaces.
     #[inline]
     pub const fn leading_spaces(mut self, render: bool) -> Self {
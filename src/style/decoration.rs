@@ -0,0 +1,145 @@
+//! Box decoration options.
+
+use embedded_graphics::{
+    draw_target::DrawTarget,
+    geometry::Size,
+    prelude::PixelColor,
+    primitives::{Primitive, PrimitiveStyleBuilder, Rectangle, RoundedRectangle, StrokeAlignment},
+    Drawable,
+};
+
+/// Background and border of a [`TextBox`].
+///
+/// The decoration is drawn before the text, covering the whole bounding box. The border is drawn
+/// inside the bounding box and the text is moved inside the border, in addition to the
+/// [`padding`].
+///
+/// There is no decoration by default.
+///
+/// The decoration is set on the [`TextBox`] using [`TextBox::with_decoration`] and not on the
+/// [`TextBoxStyle`], because the style doesn't depend on the color type of the display. Like the
+/// text color, which is part of the character style, the colors of the decoration are set
+/// together with the text box.
+///
+/// # Example
+///
+/// ```rust
+/// # use embedded_graphics::{
+/// #     mock_display::MockDisplay,
+/// #     mono_font::{ascii::FONT_6X10, MonoTextStyle},
+/// #     pixelcolor::BinaryColor,
+/// #     prelude::*,
+/// #     primitives::Rectangle,
+/// # };
+/// # let mut display: MockDisplay<BinaryColor> = MockDisplay::new();
+/// # display.set_allow_overdraw(true);
+/// use embedded_text::{style::BoxDecoration, TextBox};
+///
+/// let character_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+/// let bounding_box = Rectangle::new(Point::zero(), Size::new(60, 20));
+///
+/// TextBox::new("Hello!", bounding_box, character_style)
+///     .with_decoration(
+///         BoxDecoration::new()
+///             .with_background(BinaryColor::Off)
+///             .with_border(BinaryColor::On, 1)
+///             .with_corner_radius(3),
+///     )
+///     .draw(&mut display)?;
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+///
+/// [`TextBox`]: crate::TextBox
+/// [`TextBox::with_decoration`]: crate::TextBox::with_decoration
+/// [`TextBoxStyle`]: crate::style::TextBoxStyle
+/// [`padding`]: crate::style::TextBoxStyle::padding
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct BoxDecoration<C> {
+    /// Color used to fill the bounding box.
+    pub background_color: Option<C>,
+
+    /// Color of the border.
+    pub border_color: Option<C>,
+
+    /// Width of the border, in pixels.
+    pub border_width: u32,
+
+    /// Radius of the corners, in pixels.
+    pub corner_radius: u32,
+}
+
+impl<C> BoxDecoration<C> {
+    /// Returns a decoration that doesn't draw anything.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            background_color: None,
+            border_color: None,
+            border_width: 0,
+            corner_radius: 0,
+        }
+    }
+
+    /// Returns the width of the drawn border.
+    pub(crate) const fn border_width(&self) -> u32 {
+        if self.border_color.is_some() {
+            self.border_width
+        } else {
+            0
+        }
+    }
+}
+
+impl<C> Default for BoxDecoration<C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: PixelColor> BoxDecoration<C> {
+    /// Sets the background color.
+    #[inline]
+    pub const fn with_background(mut self, color: C) -> Self {
+        self.background_color = Some(color);
+        self
+    }
+
+    /// Sets the border color and width.
+    #[inline]
+    pub const fn with_border(mut self, color: C, width: u32) -> Self {
+        self.border_color = Some(color);
+        self.border_width = width;
+        self
+    }
+
+    /// Sets the radius of the corners.
+    #[inline]
+    pub const fn with_corner_radius(mut self, radius: u32) -> Self {
+        self.corner_radius = radius;
+        self
+    }
+
+    /// Draws the decoration into the given bounds.
+    pub(crate) fn draw<D>(&self, bounds: Rectangle, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = C>,
+    {
+        if self.background_color.is_none() && self.border_width() == 0 {
+            return Ok(());
+        }
+
+        let mut style = PrimitiveStyleBuilder::new().stroke_alignment(StrokeAlignment::Inside);
+        if let Some(color) = self.background_color {
+            style = style.fill_color(color);
+        }
+        if let Some(color) = self.border_color {
+            style = style.stroke_color(color).stroke_width(self.border_width);
+        }
+
+        let radius = Size::new(self.corner_radius, self.corner_radius);
+        RoundedRectangle::with_equal_corners(bounds, radius)
+            .into_styled(style.build())
+            .draw(target)
+    }
+}
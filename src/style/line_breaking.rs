@@ -0,0 +1,99 @@
+//! Line breaking options.
+
+/// Line breaking options.
+///
+/// The line breaking mode decides where the text is allowed to wrap to a new line.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum LineBreaking {
+    /// Only break lines at whitespace, zero-width space and soft hyphen characters.
+    ///
+    /// This is the default setting.
+    Simple,
+
+    /// Break lines according to the [Unicode Line Breaking Algorithm] (UAX #14).
+    ///
+    /// In this mode text may wrap after hyphens and slashes, between ideographs and at other
+    /// opportunities defined by the algorithm. Breaks are prohibited before closing punctuation,
+    /// and mandatory break characters (e.g. `U+2028 LINE SEPARATOR`) start a new line.
+    ///
+    /// This mode requires the `linebreak` feature.
+    ///
+    /// [Unicode Line Breaking Algorithm]: https://www.unicode.org/reports/tr14/
+    #[cfg(feature = "linebreak")]
+    Unicode,
+
+    /// Break lines between Chinese and Japanese characters, following the kinsoku shori rules.
+    ///
+    /// In addition to whitespace, text may wrap between ideographs, kana and CJK punctuation.
+    /// Closing brackets, punctuation and small kana never start a line, and opening brackets never
+    /// end a line. Other text is wrapped like in [`LineBreaking::Simple`].
+    ///
+    /// Ideographic commas and full stops (`、`, `。`, `，`, `．`) that don't fit at the end of a line
+    /// hang into the right margin instead of moving the preceding character to the next line.
+    /// Hanging punctuation is drawn outside of the bounding box of the text box.
+    Cjk,
+
+    /// Don't wrap lines.
+    ///
+    /// Lines only end at newline characters. Text that doesn't fit into the text box is cut off at
+    /// its edge, see [`TextBox::set_horizontal_offset`] to scroll such lines.
+    ///
+    /// [`TextBox::set_horizontal_offset`]: crate::TextBox::set_horizontal_offset
+    NoWrap,
+}
+
+impl LineBreaking {
+    /// Returns the default line breaking mode, which is [`LineBreaking::Simple`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::Simple
+    }
+
+    /// Returns whether lines are wrapped at the edge of the text box.
+    pub(crate) const fn wraps(self) -> bool {
+        !matches!(self, LineBreaking::NoWrap)
+    }
+}
+
+/// Line width used when lines are not wrapped.
+pub(crate) const UNLIMITED_LINE_WIDTH: u32 = i32::MAX as u32;
+
+#[cfg(test)]
+mod test {
+    use crate::style::{test::assert_line_widths, LineBreaking, TextBoxStyle};
+
+    #[track_caller]
+    fn assert_line_breaking(
+        line_breaking: LineBreaking,
+        text: &str,
+        max_chars: u32,
+        expected: &[u32],
+    ) {
+        assert_line_widths(
+            TextBoxStyle::with_line_breaking(line_breaking),
+            text,
+            max_chars,
+            expected,
+        );
+    }
+
+    #[test]
+    fn cjk_breaks_between_ideographs() {
+        assert_line_breaking(LineBreaking::Simple, "日本語の文", 3, &[3, 2]);
+        assert_line_breaking(LineBreaking::Cjk, "日本語 の文", 3, &[3, 2]);
+        assert_line_breaking(LineBreaking::Cjk, "日本語の文", 2, &[2, 2, 1]);
+    }
+
+    #[test]
+    fn cjk_kinsoku() {
+        // Closing brackets don't start a line, opening brackets don't end a line.
+        assert_line_breaking(LineBreaking::Cjk, "日本」語", 2, &[1, 2, 1]);
+        assert_line_breaking(LineBreaking::Cjk, "日「本語", 2, &[1, 2, 1]);
+    }
+
+    #[test]
+    fn cjk_hanging_punctuation() {
+        assert_line_breaking(LineBreaking::Cjk, "日本語。文", 3, &[4, 1]);
+        assert_line_breaking(LineBreaking::Cjk, "日本語」文", 3, &[2, 3]);
+    }
+}
@@ -40,6 +40,30 @@
 //! The [`tab_size`] setting sets the maximum width of a tab character. It can be specified in
 //! either pixels of number of space characters.
 //!
+//! The [`line_breaking`] setting selects where lines may be wrapped. By default, text is only
+//! wrapped at whitespace, zero-width spaces and soft hyphens. With the `linebreak` feature enabled,
+//! `LineBreaking::Unicode` wraps text according to the Unicode Line Breaking Algorithm.
+//! [`Cjk`] wraps Chinese and Japanese text between characters, following the kinsoku shori rules.
+//! [`NoWrap`] disables wrapping, lines only end at newline characters.
+//!
+//! The [`paragraph_breaking`] setting selects which break opportunities are used. The default
+//! [`Greedy`] strategy fills each line with as many words as possible. [`TotalFit`] considers the
+//! whole paragraph and avoids very loose lines, which is most useful for justified text.
+//!
+//! The [`word_break`] setting selects how words that don't fit into a line are handled. By
+//! default, words are moved to the next line and only words longer than a line are broken.
+//!
+//! The [`overflow`] setting selects how text that doesn't fit into the text box is indicated. By
+//! default, the text is simply cut off. [`Ellipsis`] replaces the end of the last visible line with
+//! an ellipsis, while [`MiddleEllipsis`] shortens single line text by replacing its middle part.
+//!
+//! The [`direction`] setting sets the base direction of paragraphs. With the `bidi` feature
+//! enabled, lines that contain right-to-left text are reordered for display, and the `Start` and
+//! `End` alignments follow the direction of each paragraph.
+//!
+//! The [`padding`] setting sets the space between the edges of the bounding box and the text.
+//! A background and a border can be drawn around the text using [`BoxDecoration`].
+//!
 //! Advanced settings
 //! -----------------
 //!
@@ -68,6 +92,8 @@
 //! | `Right`      | `false`          | `false`           |
 //! | `Center`     | `false`          | `false`           |
 //! | `Justified`  | `false`          | `false`           |
+//! | `Start`      | `true`           | `false`           |
+//! | `End`        | `false`          | `false`           |
 //!
 //! # Ways to create and apply text box styles
 //!
@@ -166,9 +192,21 @@
 //! [`line_height`]: TextBoxStyle::line_height
 //! [`paragraph_spacing`]: TextBoxStyle::paragraph_spacing
 //! [`tab_size`]: TextBoxStyle::tab_size
+//! [`line_breaking`]: TextBoxStyle::line_breaking
+//! [`paragraph_breaking`]: TextBoxStyle::paragraph_breaking
+//! [`word_break`]: TextBoxStyle::word_break
+//! [`overflow`]: TextBoxStyle::overflow
+//! [`direction`]: TextBoxStyle::direction
+//! [`padding`]: TextBoxStyle::padding
+//! [`Ellipsis`]: TextOverflow::Ellipsis
+//! [`MiddleEllipsis`]: TextOverflow::MiddleEllipsis
 //! [`height_mode`]: TextBoxStyle::height_mode
 //! [`leading_spaces`]: TextBoxStyle::leading_spaces
 //! [`trailing_spaces`]: TextBoxStyle::trailing_spaces
+//! [`Cjk`]: LineBreaking::Cjk
+//! [`NoWrap`]: LineBreaking::NoWrap
+//! [`Greedy`]: ParagraphBreaking::Greedy
+//! [`TotalFit`]: ParagraphBreaking::TotalFit
 //! [`Exact`]: HeightMode::Exact
 //! [`FitToText`]: HeightMode::FitToText
 //! [`ShrinkToText`]: HeightMode::ShrinkToText
@@ -178,26 +216,48 @@
 //! [`embedded-graphics` documentation]: https://docs.rs/embedded-graphics/0.7.1/embedded_graphics/text/index.html
 
 mod builder;
+mod decoration;
 mod height_mode;
+mod line_breaking;
+mod padding;
+mod paragraph_breaking;
+mod text_direction;
+mod text_overflow;
 mod vertical_overdraw;
+mod word_break;
 
 use core::convert::Infallible;
 
 use crate::{
-    alignment::{HorizontalAlignment, VerticalAlignment},
-    parser::Parser,
+    alignment::{
+        BaselineShift, HorizontalAlignment, InlineAlignment, Justification, VerticalAlignment,
+    },
+    parser::{ChangeTextStyle, Parser},
     plugin::{NoPlugin, PluginMarker as Plugin, PluginWrapper, ProcessingState},
     rendering::{
         cursor::LineCursor,
+        fonts::{ascent, Fonts, SelectFont},
         line_iter::{ElementHandler, LineElementParser, LineEndType},
         space_config::SpaceConfig,
+        spacing::{Borrowed, SpacedTextRenderer},
     },
-    utils::{str_width, str_width_and_left_offset},
+    utils::{graphemes, str_width, str_width_and_left_offset},
+};
+use az::SaturatingAs;
+use embedded_graphics::{
+    geometry::{Point, Size},
+    text::{renderer::TextRenderer, Baseline, LineHeight},
 };
-use embedded_graphics::text::{renderer::TextRenderer, LineHeight};
 
 pub use self::{
-    builder::TextBoxStyleBuilder, height_mode::HeightMode, vertical_overdraw::VerticalOverdraw,
+    builder::TextBoxStyleBuilder, decoration::BoxDecoration, height_mode::HeightMode,
+    line_breaking::LineBreaking, padding::Padding, paragraph_breaking::ParagraphBreaking,
+    text_direction::TextDirection, text_overflow::TextOverflow,
+    vertical_overdraw::VerticalOverdraw, word_break::WordBreak,
+};
+
+pub(crate) use self::{
+    line_breaking::UNLIMITED_LINE_WIDTH, paragraph_breaking::LineWidths, text_overflow::ElidedText,
 };
 
 /// Tab size helper
@@ -230,6 +290,36 @@ impl TabSize {
     }
 }
 
+/// Indentation helper
+///
+/// This type makes it more obvious what unit is used to define the indentation of lines.
+/// There is no indentation by default.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Indent {
+    /// Indentation as a number of pixels.
+    Pixels(u16),
+
+    /// Indentation as a number of space characters.
+    Spaces(u16),
+}
+
+impl Indent {
+    /// Returns the default indentation, which is 0 pixels.
+    #[inline]
+    pub const fn default() -> Self {
+        Self::Pixels(0)
+    }
+
+    /// Calculate the width of the indentation in pixels
+    #[inline]
+    pub(crate) fn into_pixels(self, renderer: &impl TextRenderer) -> u32 {
+        match self {
+            Indent::Pixels(px) => px as u32,
+            Indent::Spaces(n) => n as u32 * str_width(renderer, " "),
+        }
+    }
+}
+
 /// Styling options of a [`TextBox`].
 ///
 /// `TextBoxStyle` contains the font, foreground and background `PixelColor`, line spacing,
@@ -247,26 +337,78 @@ pub struct TextBoxStyle {
     /// Horizontal text alignment.
     pub alignment: HorizontalAlignment,
 
+    /// Horizontal alignment of the last line of paragraphs. `None` uses the default behaviour of
+    /// `alignment`.
+    pub last_line_alignment: Option<HorizontalAlignment>,
+
+    /// Justification mode
+    pub justification: Justification,
+
+    /// Maximum width of spaces in justified lines, in pixels.
+    pub max_word_spacing: Option<u32>,
+
     /// Vertical text alignment.
     pub vertical_alignment: VerticalAlignment,
 
     /// The height behaviour.
     pub height_mode: HeightMode,
 
+    /// Space between the edges of the bounding box and the text.
+    pub padding: Padding,
+
+    /// True to fill the whole area of the lines with the background color
+    pub fill_line_background: bool,
+
     /// Line height.
     pub line_height: LineHeight,
 
+    /// True to distribute the leading evenly above and below the glyphs of each line
+    pub half_leading: bool,
+
+    /// True to measure the height of the text using the bounding boxes of its glyphs
+    pub tight_bounds: bool,
+
     /// Paragraph spacing.
     pub paragraph_spacing: u32,
 
+    /// Space added after every character, in pixels.
+    pub letter_spacing: i32,
+
+    /// Space added to every space character, in pixels.
+    pub word_spacing: i32,
+
     /// Desired column width for tabs
     pub tab_size: TabSize,
 
+    /// Indentation of the first line of paragraphs
+    pub first_line_indent: Indent,
+
+    /// Indentation of every line of paragraphs except the first one
+    pub hanging_indent: Indent,
+
+    /// True to indent wrapped lines by the leading whitespace of their paragraph
+    pub preserve_indentation: bool,
+
     /// True to render leading spaces
     pub leading_spaces: bool,
 
     /// True to render trailing spaces
     pub trailing_spaces: bool,
+
+    /// Line breaking mode
+    pub line_breaking: LineBreaking,
+
+    /// Paragraph breaking strategy
+    pub paragraph_breaking: ParagraphBreaking,
+
+    /// Word breaking mode
+    pub word_break: WordBreak,
+
+    /// Text overflow mode
+    pub overflow: TextOverflow,
+
+    /// Text direction
+    pub direction: TextDirection,
 }
 
 impl TextBoxStyle {
@@ -282,6 +424,23 @@ impl TextBoxStyle {
         TextBoxStyleBuilder::new().alignment(alignment).build()
     }
 
+    /// Creates a new text box style with the given last line alignment.
+    #[inline]
+    pub const fn with_last_line_alignment(alignment: HorizontalAlignment) -> TextBoxStyle {
+        TextBoxStyleBuilder::new()
+            .last_line_alignment(alignment)
+            .build()
+    }
+
+    /// Creates a new text box style with justified alignment and the given justification mode.
+    #[inline]
+    pub const fn with_justification(justification: Justification) -> TextBoxStyle {
+        TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .justification(justification)
+            .build()
+    }
+
     /// Creates a new text box style with the given vertical alignment.
     #[inline]
     pub const fn with_vertical_alignment(alignment: VerticalAlignment) -> TextBoxStyle {
@@ -298,6 +457,12 @@ impl TextBoxStyle {
         TextBoxStyleBuilder::new().height_mode(mode).build()
     }
 
+    /// Creates a new text box style with the given padding.
+    #[inline]
+    pub const fn with_padding(padding: Padding) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().padding(padding).build()
+    }
+
     /// Creates a new text box style with the given line height.
     #[inline]
     pub const fn with_line_height(line_height: LineHeight) -> TextBoxStyle {
@@ -312,11 +477,69 @@ impl TextBoxStyle {
             .build()
     }
 
+    /// Creates a new text box style with the given letter spacing.
+    #[inline]
+    pub const fn with_letter_spacing(spacing: i32) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().letter_spacing(spacing).build()
+    }
+
+    /// Creates a new text box style with the given word spacing.
+    #[inline]
+    pub const fn with_word_spacing(spacing: i32) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().word_spacing(spacing).build()
+    }
+
     /// Creates a new text box style with the given tab size.
     #[inline]
     pub const fn with_tab_size(tab_size: TabSize) -> TextBoxStyle {
         TextBoxStyleBuilder::new().tab_size(tab_size).build()
     }
+
+    /// Creates a new text box style with the given first line indentation.
+    #[inline]
+    pub const fn with_first_line_indent(indent: Indent) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().first_line_indent(indent).build()
+    }
+
+    /// Creates a new text box style with the given hanging indentation.
+    #[inline]
+    pub const fn with_hanging_indent(indent: Indent) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().hanging_indent(indent).build()
+    }
+
+    /// Creates a new text box style with the given line breaking mode.
+    #[inline]
+    pub const fn with_line_breaking(line_breaking: LineBreaking) -> TextBoxStyle {
+        TextBoxStyleBuilder::new()
+            .line_breaking(line_breaking)
+            .build()
+    }
+
+    /// Creates a new text box style with the given paragraph breaking strategy.
+    #[inline]
+    pub const fn with_paragraph_breaking(paragraph_breaking: ParagraphBreaking) -> TextBoxStyle {
+        TextBoxStyleBuilder::new()
+            .paragraph_breaking(paragraph_breaking)
+            .build()
+    }
+
+    /// Creates a new text box style with the given word breaking mode.
+    #[inline]
+    pub const fn with_word_break(word_break: WordBreak) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().word_break(word_break).build()
+    }
+
+    /// Creates a new text box style with the given text overflow mode.
+    #[inline]
+    pub const fn with_overflow(overflow: TextOverflow) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().overflow(overflow).build()
+    }
+
+    /// Creates a new text box style with the given text direction.
+    #[inline]
+    pub const fn with_direction(direction: TextDirection) -> TextBoxStyle {
+        TextBoxStyleBuilder::new().direction(direction).build()
+    }
 }
 
 /// Information about a line.
@@ -326,6 +549,10 @@ pub(crate) struct LineMeasurement {
     /// Maximum line width in pixels.
     pub max_line_width: u32,
 
+    /// The width in pixels where the line was wrapped. Less than `max_line_width` if the
+    /// paragraph breaking strategy ended the line early.
+    pub wrap_width: u32,
+
     /// Width in pixels, using the default space width returned by the text renderer.
     pub width: u32,
 
@@ -334,6 +561,45 @@ pub(crate) struct LineMeasurement {
 
     /// Number of spaces in the current line.
     pub space_count: u32,
+
+    /// Number of printed characters (grapheme clusters) in the current line.
+    pub glyph_count: u32,
+
+    /// Width of the whitespace before the first printed character, in pixels.
+    pub leading_width: u32,
+
+    /// Top and bottom of the printed glyphs, relative to the top of the line.
+    pub ink_bounds: Option<(i32, i32)>,
+
+    /// Distance between the top of the line and the baseline shared by its fonts.
+    pub baseline: u32,
+
+    /// Height of the line, which fits every font used in the line.
+    pub line_height: u32,
+}
+
+/// Information about a text.
+#[derive(Debug, Copy, Clone)]
+#[must_use]
+pub(crate) struct TextMeasurement<'a> {
+    /// Height of the text in pixels.
+    pub height: u32,
+
+    /// Width of the widest line in pixels.
+    pub width: u32,
+
+    /// Number of rows above the glyphs of the first line that are not included in the height.
+    pub trimmed_top: u32,
+
+    /// Number of rows below the glyphs of the last line that are not included in the height.
+    pub trimmed_bottom: u32,
+
+    /// True if some lines are taller or shorter than the character style, e.g. because they
+    /// contain a different font.
+    pub varying_line_heights: bool,
+
+    /// The displayed text if its middle part is replaced by an ellipsis.
+    pub elided_text: Option<ElidedText<'a>>,
 }
 
 impl LineMeasurement {
@@ -350,16 +616,59 @@ impl LineMeasurement {
 }
 
 struct MeasureLineElementHandler<'a, S> {
-    style: &'a S,
+    style: &'a mut S,
     trailing_spaces: bool,
+    measure_ink: bool,
     cursor: u32,
     pos: u32,
     right: u32,
     partial_space_count: u32,
     space_count: u32,
+    glyph_count: u32,
+    leading_width: Option<u32>,
+
+    /// Top and bottom of the printed glyphs, relative to the baseline.
+    ink_bounds: Option<(i32, i32)>,
+
+    /// Largest distance above and below the baseline of the fonts used in the line.
+    ascent: u32,
+    descent: u32,
+
+    /// Distance between the baseline of the line and the top of the current font, including the
+    /// baseline shift.
+    font_ascent: i32,
+
+    /// Height of the tallest object that is centered in the line.
+    centered_height: u32,
+}
+
+impl<'a, S: TextRenderer + SelectFont> MeasureLineElementHandler<'a, S> {
+    fn add_current_font(&mut self) {
+        self.font_ascent = ascent(self.style).saturating_as::<i32>() + self.style.baseline_shift();
+        let descent = self.style.line_height().saturating_as::<i32>() - self.font_ascent;
+
+        self.ascent = self.ascent.max(self.font_ascent.saturating_as());
+        self.descent = self.descent.max(descent.saturating_as());
+    }
 }
 
 impl<'a, S> MeasureLineElementHandler<'a, S> {
+    /// Returns the distance between the top of the line and the baseline, and the height of the
+    /// line.
+    fn line_metrics(&self) -> (u32, u32) {
+        let height = self.ascent + self.descent;
+        let extra = self.centered_height.saturating_sub(height);
+
+        (self.ascent + extra / 2, height + extra)
+    }
+
+    fn extend_ink(&mut self, top: i32, bottom: i32) {
+        self.ink_bounds = Some(match self.ink_bounds {
+            Some((t, b)) => (t.min(top), b.max(bottom)),
+            None => (top, bottom),
+        });
+    }
+
     fn space_count(&self) -> u32 {
         if self.trailing_spaces {
             self.partial_space_count
@@ -377,16 +686,16 @@ impl<'a, S> MeasureLineElementHandler<'a, S> {
     }
 }
 
-impl<'a, S: TextRenderer> ElementHandler for MeasureLineElementHandler<'a, S> {
+impl<'a, S: TextRenderer + SelectFont> ElementHandler for MeasureLineElementHandler<'a, S> {
     type Error = Infallible;
     type Color = S::Color;
 
     fn measure(&self, st: &str) -> u32 {
-        str_width(self.style, st)
+        str_width(&*self.style, st)
     }
 
     fn measure_width_and_left_offset(&self, st: &str) -> (u32, u32) {
-        str_width_and_left_offset(self.style, st)
+        str_width_and_left_offset(&*self.style, st)
     }
 
     fn whitespace(&mut self, _st: &str, count: u32, width: u32) -> Result<(), Self::Error> {
@@ -398,10 +707,24 @@ impl<'a, S: TextRenderer> ElementHandler for MeasureLineElementHandler<'a, S> {
     }
 
     fn printed_characters(&mut self, str: &str, width: Option<u32>) -> Result<(), Self::Error> {
+        self.leading_width.get_or_insert(self.cursor);
         self.cursor += width.unwrap_or_else(|| self.measure(str));
         self.pos = self.pos.max(self.cursor);
         self.right = self.pos;
         self.space_count = self.partial_space_count;
+        self.glyph_count += graphemes(str).count() as u32;
+
+        if self.measure_ink {
+            let bounds = self
+                .style
+                .measure_string(str, Point::zero(), Baseline::Top)
+                .bounding_box;
+            if !bounds.is_zero_sized() {
+                let top = bounds.top_left.y - self.font_ascent;
+                let bottom = top + bounds.size.height.saturating_as::<i32>();
+                self.extend_ink(top, bottom);
+            }
+        }
 
         Ok(())
     }
@@ -411,9 +734,129 @@ impl<'a, S: TextRenderer> ElementHandler for MeasureLineElementHandler<'a, S> {
 
         Ok(())
     }
+
+    fn inline_object(
+        &mut self,
+        _plugin: usize,
+        _index: usize,
+        size: Size,
+        alignment: InlineAlignment,
+    ) -> Result<(), Self::Error> {
+        self.leading_width.get_or_insert(self.cursor);
+        self.cursor += size.width;
+        self.pos = self.pos.max(self.cursor);
+        self.right = self.pos;
+        self.space_count = self.partial_space_count;
+
+        match alignment {
+            InlineAlignment::Baseline => {
+                self.ascent = self.ascent.max(size.height);
+                if self.measure_ink {
+                    self.extend_ink(-size.height.saturating_as::<i32>(), 0);
+                }
+            }
+            // The position depends on the height of the whole line.
+            InlineAlignment::Middle => self.centered_height = self.centered_height.max(size.height),
+        }
+
+        Ok(())
+    }
+
+    fn change_text_style(
+        &mut self,
+        change: ChangeTextStyle<Self::Color>,
+    ) -> Result<(), Self::Error> {
+        match change {
+            ChangeTextStyle::Reset => {
+                self.style.select_font(None);
+                self.style.shift_baseline(BaselineShift::None);
+            }
+            ChangeTextStyle::Font(font) => self.style.select_font(font),
+            ChangeTextStyle::BaselineShift(shift) => self.style.shift_baseline(shift),
+            _ => return Ok(()),
+        }
+        self.add_current_font();
+
+        Ok(())
+    }
 }
 
 impl TextBoxStyle {
+    /// Returns the indentation of a line in pixels, including the indentation added by plugins.
+    ///
+    /// `first_line` is true for the first line of a paragraph. `wrap_indent` is the preserved
+    /// indentation of the paragraph, as returned by [`TextBoxStyle::wrap_indent`].
+    pub(crate) fn indent<'a, S, M>(
+        &self,
+        plugin: &PluginWrapper<'a, M, S::Color>,
+        renderer: &S,
+        first_line: bool,
+        wrap_indent: u32,
+    ) -> u32
+    where
+        S: TextRenderer,
+        M: Plugin<'a, S::Color>,
+    {
+        let indent = if first_line {
+            self.first_line_indent.into_pixels(renderer)
+        } else {
+            self.hanging_indent.into_pixels(renderer) + wrap_indent
+        };
+
+        indent + plugin.line_indent_width(renderer)
+    }
+
+    /// Returns the indentation of the wrapped lines of a paragraph, given the measurement of its
+    /// first line.
+    ///
+    /// The indentation is limited to half of the line width, so that deeply indented lines still
+    /// have room for text.
+    pub(crate) fn wrap_indent(&self, measurement: &LineMeasurement) -> u32 {
+        if self.preserve_indentation {
+            measurement
+                .leading_width
+                .min(measurement.max_line_width / 2)
+        } else {
+            0
+        }
+    }
+
+    /// Returns the horizontal alignment of a line.
+    pub(crate) fn line_alignment(&self, measurement: &LineMeasurement) -> HorizontalAlignment {
+        if !measurement.last_line() {
+            return self.alignment;
+        }
+
+        match (self.last_line_alignment, self.alignment) {
+            (Some(alignment), _) => alignment,
+            (None, HorizontalAlignment::Justified) => HorizontalAlignment::Start,
+            (None, alignment) => alignment,
+        }
+    }
+
+    /// Measures a single line of text, without the line breaks chosen for the rest of its
+    /// paragraph.
+    #[cfg(test)]
+    pub(crate) fn measure_line<'a, S, M>(
+        &self,
+        plugin: &PluginWrapper<'a, M, S::Color>,
+        character_style: &mut S,
+        parser: &mut Parser<'a, S::Color>,
+        max_line_width: u32,
+    ) -> LineMeasurement
+    where
+        S: TextRenderer + SelectFont,
+        M: Plugin<'a, S::Color>,
+    {
+        self.measure_paragraph_line(
+            &mut LineWidths::default(),
+            plugin,
+            character_style,
+            parser,
+            max_line_width,
+        )
+    }
+
     /// Measure the width and count spaces in a single line of text.
     ///
     /// Returns (width, rendered space count, carried token)
@@ -421,44 +864,87 @@ impl TextBoxStyle {
     /// Instead of peeking ahead when processing tokens, this function advances the parser before
     /// processing a token. If a token opens a new line, it will be returned as the carried token.
     /// If the carried token is `None`, the parser has finished processing the text.
+    ///
+    /// Font changes in the line are applied to `character_style`. `line_widths` keeps the line
+    /// breaks chosen for the current paragraph between its lines.
     #[inline]
-    pub(crate) fn measure_line<'a, S, M>(
+    pub(crate) fn measure_paragraph_line<'a, S, M>(
         &self,
+        line_widths: &mut LineWidths,
         plugin: &PluginWrapper<'a, M, S::Color>,
-        character_style: &S,
+        character_style: &mut S,
         parser: &mut Parser<'a, S::Color>,
         max_line_width: u32,
     ) -> LineMeasurement
     where
-        S: TextRenderer,
+        S: TextRenderer + SelectFont,
         M: Plugin<'a, S::Color>,
     {
-        let cursor = LineCursor::new(max_line_width, self.tab_size.into_pixels(character_style));
+        let line_width = if self.line_breaking.wraps() {
+            self.paragraph_breaking.line_width(
+                line_widths,
+                plugin,
+                &*character_style,
+                parser,
+                max_line_width,
+                self.leading_spaces,
+            )
+        } else {
+            UNLIMITED_LINE_WIDTH
+        };
+        let cursor = LineCursor::new(line_width, self.tab_size.into_pixels(&*character_style));
 
         let mut iter = LineElementParser::new(
             parser,
             plugin,
             cursor,
-            SpaceConfig::new(str_width(character_style, " "), None),
+            SpaceConfig::new(str_width(&*character_style, " "), None),
             self,
         );
 
         let mut handler = MeasureLineElementHandler {
             style: character_style,
             trailing_spaces: self.trailing_spaces,
+            measure_ink: self.tight_bounds,
 
             cursor: 0,
             pos: 0,
             right: 0,
             partial_space_count: 0,
             space_count: 0,
+            glyph_count: 0,
+            leading_width: None,
+            ink_bounds: None,
+            ascent: 0,
+            descent: 0,
+            font_ascent: 0,
+            centered_height: 0,
         };
+        handler.add_current_font();
         let last_token = iter.process(&mut handler).unwrap();
+        line_widths.end_line(last_token);
+        let (baseline, line_height) = handler.line_metrics();
+
+        if handler.measure_ink && handler.centered_height > 0 {
+            let top = InlineAlignment::Middle.offset(handler.centered_height, 0, line_height)
+                - baseline.saturating_as::<i32>();
+            handler.extend_ink(top, top + handler.centered_height.saturating_as::<i32>());
+        }
+        let ink_bounds = handler.ink_bounds.map(|(top, bottom)| {
+            let baseline = baseline.saturating_as::<i32>();
+            (top + baseline, bottom + baseline)
+        });
 
         LineMeasurement {
             max_line_width,
+            wrap_width: line_width,
             width: handler.right(),
             space_count: handler.space_count(),
+            glyph_count: handler.glyph_count,
+            leading_width: handler.leading_width.unwrap_or(0),
+            ink_bounds,
+            baseline,
+            line_height,
             line_end_type: last_token,
         }
     }
@@ -505,54 +991,136 @@ impl TextBoxStyle {
         S: TextRenderer,
     {
         let plugin = PluginWrapper::new(NoPlugin::new());
-        self.measure_text_height_impl(plugin, character_style, text, max_width)
+        self.measure_text_impl(plugin, character_style, &[], text, max_width)
+            .height
     }
 
-    pub(crate) fn measure_text_height_impl<'a, S, M>(
+    /// Measures the text when rendered using a given width.
+    pub(crate) fn measure_text_impl<'a, S, M>(
         &self,
         plugin: PluginWrapper<'a, M, S::Color>,
         character_style: &S,
+        fonts: &[S],
         text: &'a str,
         max_width: u32,
-    ) -> u32
+    ) -> TextMeasurement<'a>
     where
         S: TextRenderer,
         M: Plugin<'a, S::Color>,
     {
-        let mut parser = Parser::parse(text);
+        let character_style =
+            &mut SpacedTextRenderer::new(Fonts::new(character_style, fonts, Borrowed), self);
+        let mut parser = Parser::parse(text).with_line_breaking(self.line_breaking);
         let base_line_height = character_style.line_height();
-        let line_height = self.line_height.to_absolute(base_line_height);
-        let mut height = base_line_height;
+        let leading = if self.half_leading {
+            self.line_height
+                .to_absolute(base_line_height)
+                .saturating_sub(base_line_height)
+        } else {
+            0
+        };
+
+        let indent = self.indent(&plugin, &*character_style, true, 0);
+        if let Some(elided_text) =
+            self.overflow
+                .elide_middle(&*character_style, text, max_width.saturating_sub(indent))
+        {
+            // Elided text is displayed in a single line.
+            return TextMeasurement {
+                height: base_line_height + leading,
+                width: elided_text.width(&*character_style) + indent,
+                trimmed_top: 0,
+                trimmed_bottom: 0,
+                varying_line_heights: false,
+                elided_text: Some(elided_text),
+            };
+        }
+
+        let mut width = 0;
+
+        // Vertical position and height of the current line, and the extent of the glyphs.
+        let mut line_top = leading / 2;
+        let mut line_height = base_line_height;
+        let mut ink_bounds = None;
+        let mut varying_line_heights = false;
 
         plugin.set_state(ProcessingState::Measure);
 
         let mut prev_end = LineEndType::EndOfText;
+        let mut first_line = true;
+        let mut wrap_indent = 0;
+        let mut line_widths = LineWidths::default();
 
         loop {
             plugin.new_line();
-            let lm = self.measure_line(&plugin, character_style, &mut parser, max_width);
+            let indent = self.indent(&plugin, &*character_style, first_line, wrap_indent);
+            let lm = self.measure_paragraph_line(
+                &mut line_widths,
+                &plugin,
+                character_style,
+                &mut parser,
+                max_width.saturating_sub(indent),
+            );
+            width = width.max(lm.width + indent);
+            if first_line {
+                wrap_indent = self.wrap_indent(&lm);
+            }
+            first_line = lm.line_end_type.starts_paragraph(first_line);
+            varying_line_heights |= lm.line_height != base_line_height;
+
+            if prev_end != LineEndType::LineBreak {
+                line_height = lm.line_height;
+            } else if !lm.is_empty() {
+                line_top += self.line_height.to_absolute(line_height);
+                line_height = lm.line_height;
+            }
 
-            if prev_end == LineEndType::LineBreak && !lm.is_empty() {
-                height += line_height;
+            if let Some((top, bottom)) = lm.ink_bounds {
+                let (top, bottom) = (line_top as i32 + top, line_top as i32 + bottom);
+                ink_bounds = Some(match ink_bounds {
+                    Some((t, b)) => (top.min(t), bottom.max(b)),
+                    None => (top, bottom),
+                });
             }
 
             match lm.line_end_type {
                 LineEndType::CarriageReturn | LineEndType::LineBreak => {}
-                LineEndType::NewLine => height += line_height + self.paragraph_spacing,
-                LineEndType::EndOfText => return height,
+                LineEndType::NewLine => {
+                    line_top += self.line_height.to_absolute(line_height) + self.paragraph_spacing;
+                }
+                LineEndType::EndOfText => break,
             }
             prev_end = lm.line_end_type;
         }
+
+        let height = line_top + line_height + leading - leading / 2;
+        let (trimmed_top, trimmed_bottom) = match ink_bounds {
+            Some((top, bottom)) => (
+                top.saturating_as::<u32>(),
+                (height as i32 - bottom).saturating_as::<u32>(),
+            ),
+            None => (0, 0),
+        };
+
+        TextMeasurement {
+            height: height.saturating_sub(trimmed_top + trimmed_bottom),
+            width,
+            trimmed_top,
+            trimmed_bottom,
+            varying_line_heights,
+            elided_text: None,
+        }
     }
 }
 
 #[cfg(test)]
-mod test {
+pub(crate) mod test {
     use crate::{
         alignment::*,
         parser::Parser,
         plugin::{NoPlugin, PluginWrapper},
-        style::{builder::TextBoxStyleBuilder, TextBoxStyle},
+        style::{builder::TextBoxStyleBuilder, Indent, LineWidths, TextBoxStyle},
+        utils::test::{InkTestFont, TestFont},
     };
     use embedded_graphics::{
         mono_font::{ascii::FONT_6X9, MonoTextStyleBuilder},
@@ -560,6 +1128,41 @@ mod test {
         text::{renderer::TextRenderer, LineHeight},
     };
 
+    /// Measures `text` line by line and compares the line widths, in `FONT_6X9` characters.
+    #[track_caller]
+    pub(crate) fn assert_line_widths(
+        style: TextBoxStyle,
+        text: &str,
+        max_chars: u32,
+        expected: &[u32],
+    ) {
+        let mut character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let plugin = PluginWrapper::new(NoPlugin::new());
+        let mut parser = Parser::parse(text).with_line_breaking(style.line_breaking);
+
+        let mut line_widths = LineWidths::default();
+        let mut widths = vec![];
+        loop {
+            let lm = style.measure_paragraph_line(
+                &mut line_widths,
+                &plugin,
+                &mut character_style,
+                &mut parser,
+                max_chars * 6,
+            );
+            widths.push(lm.width / 6);
+            if lm.last_line() {
+                break;
+            }
+        }
+
+        assert_eq!(widths, expected);
+    }
+
     #[test]
     fn no_infinite_loop() {
         let character_style = MonoTextStyleBuilder::new()
@@ -657,7 +1260,7 @@ mod test {
 
     #[test]
     fn test_measure_line() {
-        let character_style = MonoTextStyleBuilder::new()
+        let mut character_style = MonoTextStyleBuilder::new()
             .font(&FONT_6X9)
             .text_color(BinaryColor::On)
             .build();
@@ -671,7 +1274,7 @@ mod test {
         let mut plugin = PluginWrapper::new(NoPlugin::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            &mut character_style,
             &mut text,
             6 * FONT_6X9.character_size.width,
         );
@@ -680,7 +1283,7 @@ mod test {
 
     #[test]
     fn test_measure_line_counts_nbsp() {
-        let character_style = MonoTextStyleBuilder::new()
+        let mut character_style = MonoTextStyleBuilder::new()
             .font(&FONT_6X9)
             .text_color(BinaryColor::On)
             .build();
@@ -694,7 +1297,7 @@ mod test {
         let mut plugin = PluginWrapper::new(NoPlugin::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            &mut character_style,
             &mut text,
             5 * FONT_6X9.character_size.width,
         );
@@ -749,12 +1352,192 @@ mod test {
     }
 
     #[test]
-    fn soft_hyphenated_line_width_includes_hyphen_width() {
+    fn height_with_middle_ellipsis() {
+        use crate::style::TextOverflow;
+
         let character_style = MonoTextStyleBuilder::new()
             .font(&FONT_6X9)
             .text_color(BinaryColor::On)
             .build();
 
+        let style = TextBoxStyleBuilder::new()
+            .overflow(TextOverflow::MiddleEllipsis(".."))
+            .build();
+
+        // Elided text is displayed in a single line.
+        let height = style.measure_text_height(&character_style, "abcdefghij", 36);
+        assert_eq!(height, 9);
+
+        // Text with line breaks is not elided.
+        let height = style.measure_text_height(
+            &character_style,
+            "abcdefghij
+k",
+            36,
+        );
+        assert_eq!(height, 3 * 9);
+    }
+
+    #[test]
+    fn height_with_letter_and_word_spacing() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            // (letter spacing; word spacing; text; max width; number of expected lines)
+            (0, 0, "abc abc", 48, 1),
+            (2, 0, "abc abc", 48, 2),
+            (2, 0, "abc abc", 56, 1),
+            (-1, 0, "abcd abcd", 45, 1),
+            (0, 6, "a b c", 36, 2),
+            (0, 6, "a b c", 42, 1),
+        ];
+
+        for (letter_spacing, word_spacing, text, width, expected_n_lines) in data {
+            let style = TextBoxStyleBuilder::new()
+                .letter_spacing(letter_spacing)
+                .word_spacing(word_spacing)
+                .build();
+
+            let height = style.measure_text_height(&character_style, text, width);
+            let expected_height = expected_n_lines * character_style.line_height();
+            assert_eq!(
+                height, expected_height,
+                r#"Height of "{}" is {} but is expected to be {}"#,
+                text, height, expected_height
+            );
+        }
+    }
+
+    #[test]
+    fn height_with_indentation() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            // (first line indent; hanging indent; text; max width; number of expected lines)
+            (Indent::Pixels(0), Indent::Pixels(0), "abc def", 42, 1),
+            (Indent::Spaces(1), Indent::Pixels(0), "abc def", 42, 2),
+            (Indent::Spaces(1), Indent::Pixels(0), "abc\ndef", 24, 2),
+            (Indent::Pixels(0), Indent::Pixels(0), "ab cd ef gh", 30, 2),
+            (Indent::Pixels(0), Indent::Pixels(6), "ab cd ef gh", 30, 3),
+        ];
+
+        for (first_line_indent, hanging_indent, text, width, expected_n_lines) in data {
+            let style = TextBoxStyleBuilder::new()
+                .first_line_indent(first_line_indent)
+                .hanging_indent(hanging_indent)
+                .build();
+
+            let height = style.measure_text_height(&character_style, text, width);
+            let expected_height = expected_n_lines * character_style.line_height();
+            assert_eq!(
+                height, expected_height,
+                r#"Height of "{}" is {} but is expected to be {}"#,
+                text, height, expected_height
+            );
+        }
+    }
+
+    #[test]
+    fn height_with_preserved_indentation() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            // (preserve indentation; hanging indent; text; max width; number of expected lines)
+            (false, Indent::Pixels(0), "  ab cd efg hi", 42, 2),
+            (true, Indent::Pixels(0), "  ab cd efg hi", 42, 3),
+            (true, Indent::Pixels(0), "  ab cd\nefg hi", 42, 2),
+            (true, Indent::Pixels(0), "ab cd efghi", 30, 2),
+            (true, Indent::Spaces(1), "ab cd efghi", 30, 3),
+        ];
+
+        for (preserve_indentation, hanging_indent, text, width, expected_n_lines) in data {
+            let style = TextBoxStyleBuilder::new()
+                .preserve_indentation(preserve_indentation)
+                .hanging_indent(hanging_indent)
+                .build();
+
+            let height = style.measure_text_height(&character_style, text, width);
+            let expected_height = expected_n_lines * character_style.line_height();
+            assert_eq!(
+                height, expected_height,
+                r#"Height of "{}" is {} but is expected to be {}"#,
+                text, height, expected_height
+            );
+        }
+    }
+
+    #[test]
+    fn height_with_half_leading() {
+        let character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
+        let data = [
+            // (line height; text; expected height)
+            (LineHeight::Pixels(13), "", 13),
+            (LineHeight::Pixels(13), "abc\ndef", 26),
+            (LineHeight::Pixels(13), "abc\ndef\n", 39),
+            (LineHeight::Pixels(7), "abc\ndef", 16),
+        ];
+
+        for (line_height, text, expected_height) in data {
+            let style = TextBoxStyleBuilder::new()
+                .line_height(line_height)
+                .half_leading(true)
+                .build();
+
+            let height = style.measure_text_height(&character_style, text, 60);
+            assert_eq!(
+                height, expected_height,
+                r#"Height of "{}" is {} but is expected to be {}"#,
+                text, height, expected_height
+            );
+        }
+    }
+
+    #[test]
+    fn height_with_tight_bounds() {
+        let character_style = InkTestFont(TestFont::new(BinaryColor::On, BinaryColor::Off));
+
+        let data = [
+            // (text; expected height)
+            ("", 10),
+            ("00", 7),
+            ("0j", 10),
+            ("00 00", 17),
+            ("00\n", 7),
+            ("00\n0j", 20),
+        ];
+
+        for (text, expected_height) in data {
+            let style = TextBoxStyleBuilder::new().tight_bounds(true).build();
+
+            let height = style.measure_text_height(&character_style, text, 20);
+            assert_eq!(
+                height, expected_height,
+                r#"Height of "{}" is {} but is expected to be {}"#,
+                text, height, expected_height
+            );
+        }
+    }
+
+    #[test]
+    fn soft_hyphenated_line_width_includes_hyphen_width() {
+        let mut character_style = MonoTextStyleBuilder::new()
+            .font(&FONT_6X9)
+            .text_color(BinaryColor::On)
+            .build();
+
         let style = TextBoxStyleBuilder::new()
             .line_height(LineHeight::Pixels(11))
             .build();
@@ -762,7 +1545,7 @@ mod test {
         let mut plugin = PluginWrapper::new(NoPlugin::new());
         let lm = style.measure_line(
             &mut plugin,
-            &character_style,
+            &mut character_style,
             &mut Parser::parse("soft\u{AD}hyphen"),
             50,
         );
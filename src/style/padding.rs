@@ -0,0 +1,104 @@
+//! Padding options.
+
+use az::SaturatingAs;
+use embedded_graphics::{
+    geometry::{Point, Size},
+    primitives::Rectangle,
+};
+
+/// Space between the edges of the text box and the text, in pixels.
+///
+/// There is no padding by default.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct Padding {
+    /// Space above the text.
+    pub top: u32,
+
+    /// Space to the right of the text.
+    pub right: u32,
+
+    /// Space below the text.
+    pub bottom: u32,
+
+    /// Space to the left of the text.
+    pub left: u32,
+}
+
+impl Padding {
+    /// Returns a padding with the given size on each side.
+    #[inline]
+    pub const fn new(top: u32, right: u32, bottom: u32, left: u32) -> Self {
+        Self {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Returns a padding with the same size on every side.
+    #[inline]
+    pub const fn all(padding: u32) -> Self {
+        Self::new(padding, padding, padding, padding)
+    }
+
+    /// Returns a padding with the given vertical and horizontal sizes.
+    #[inline]
+    pub const fn symmetric(vertical: u32, horizontal: u32) -> Self {
+        Self::new(vertical, horizontal, vertical, horizontal)
+    }
+
+    /// Returns a padding of zero pixels.
+    #[inline]
+    pub const fn zero() -> Self {
+        Self::all(0)
+    }
+
+    /// Returns the sum of the left and right padding.
+    #[inline]
+    pub const fn horizontal(&self) -> u32 {
+        self.left.saturating_add(self.right)
+    }
+
+    /// Returns the sum of the top and bottom padding.
+    #[inline]
+    pub const fn vertical(&self) -> u32 {
+        self.top.saturating_add(self.bottom)
+    }
+
+    /// Returns the area of `bounds` that is left for the text.
+    pub(crate) fn apply(&self, bounds: Rectangle) -> Rectangle {
+        Rectangle::new(
+            bounds.top_left + Point::new(self.left.saturating_as(), self.top.saturating_as()),
+            Size::new(
+                bounds.size.width.saturating_sub(self.horizontal()),
+                bounds.size.height.saturating_sub(self.vertical()),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use embedded_graphics::{
+        geometry::{Point, Size},
+        primitives::Rectangle,
+    };
+
+    use crate::style::Padding;
+
+    #[test]
+    fn padding_shrinks_bounds() {
+        let bounds = Rectangle::new(Point::new(10, 20), Size::new(30, 40));
+
+        assert_eq!(Padding::zero().apply(bounds), bounds);
+        assert_eq!(
+            Padding::new(1, 2, 3, 4).apply(bounds),
+            Rectangle::new(Point::new(14, 21), Size::new(24, 36))
+        );
+        assert_eq!(
+            Padding::all(25).apply(bounds),
+            Rectangle::new(Point::new(35, 45), Size::new(0, 0))
+        );
+    }
+}
@@ -0,0 +1,477 @@
+//! Paragraph breaking options.
+
+use embedded_graphics::text::renderer::TextRenderer;
+
+use crate::{
+    parser::{Parser, Token},
+    plugin::{PluginMarker as Plugin, PluginWrapper},
+    rendering::line_iter::LineEndType,
+    utils::str_width,
+};
+
+/// Paragraph breaking options.
+///
+/// The paragraph breaking strategy decides which of the possible line break opportunities are
+/// used to wrap a paragraph.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum ParagraphBreaking {
+    /// Put as many words on each line as possible.
+    ///
+    /// This is the default setting.
+    Greedy,
+
+    /// Choose line breaks that minimize the uneven spacing over the whole paragraph.
+    ///
+    /// This is an implementation of the Knuth-Plass "total fit" line breaking algorithm. It
+    /// produces evenly filled lines, which is most visible with [`Justified`] text.
+    ///
+    /// The algorithm uses a fixed amount of memory. If a single line would contain more than 32
+    /// break opportunities, or the paragraph contains more than `max_breakpoints` break
+    /// opportunities, the whole paragraph is broken greedily. Paragraphs containing tab
+    /// characters, carriage returns or cursor movements are also broken greedily.
+    ///
+    /// The line breaks of the first 8 lines are kept while the paragraph is laid out. Longer
+    /// paragraphs are processed again after every 8 lines, which results in the same line breaks.
+    ///
+    /// [`Justified`]: crate::alignment::HorizontalAlignment::Justified
+    TotalFit {
+        /// The maximum number of break opportunities to consider.
+        max_breakpoints: u16,
+    },
+}
+
+impl ParagraphBreaking {
+    /// Returns the default paragraph breaking strategy, which is [`ParagraphBreaking::Greedy`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::Greedy
+    }
+
+    /// Returns the width that should be filled by the next line of text.
+    ///
+    /// The returned width is less than or equal to `max_line_width`. Greedily filling a line of
+    /// the returned width results in the chosen line break. `line_widths` keeps the line breaks
+    /// chosen for the current paragraph.
+    pub(crate) fn line_width<'a, S, M>(
+        self,
+        line_widths: &mut LineWidths,
+        plugin: &PluginWrapper<'a, M, S::Color>,
+        character_style: &S,
+        parser: &Parser<'a, S::Color>,
+        max_line_width: u32,
+        leading_spaces: bool,
+    ) -> u32
+    where
+        S: TextRenderer,
+        M: Plugin<'a, S::Color>,
+    {
+        match self {
+            ParagraphBreaking::Greedy => max_line_width,
+            ParagraphBreaking::TotalFit { max_breakpoints } => {
+                if line_widths.greedy {
+                    return max_line_width;
+                }
+
+                if let Some(width) = line_widths.next(max_line_width) {
+                    return width;
+                }
+
+                let mut breaker = TotalFit::new(
+                    max_line_width,
+                    str_width(character_style, " "),
+                    leading_spaces,
+                );
+
+                match breaker.line_widths(plugin, character_style, parser, max_breakpoints) {
+                    Some(planned) => {
+                        *line_widths = planned;
+                        planned.widths[0] as u32
+                    }
+                    None => {
+                        line_widths.greedy = true;
+                        max_line_width
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The number of lines whose widths are kept by [`LineWidths`].
+const PLANNED_LINES: usize = 8;
+
+/// The line widths chosen by total fit paragraph breaking for the next lines of a paragraph.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct LineWidths {
+    /// The maximum line width that was used to choose the line breaks.
+    max_line_width: u32,
+
+    /// The natural widths of the planned lines.
+    widths: [u16; PLANNED_LINES],
+
+    /// The number of planned lines.
+    len: usize,
+
+    /// The index of the next line.
+    next: usize,
+
+    /// The paragraph can't be broken optimally, the rest of it is broken greedily.
+    greedy: bool,
+}
+
+impl LineWidths {
+    /// Returns the planned width of the next line, if it was planned for the same line width.
+    fn next(&self, max_line_width: u32) -> Option<u32> {
+        if self.max_line_width == max_line_width && self.next < self.len {
+            Some(self.widths[self.next] as u32)
+        } else {
+            None
+        }
+    }
+
+    /// Moves to the next line. The planned line breaks are discarded at the end of a paragraph.
+    pub(crate) fn end_line(&mut self, end_type: LineEndType) {
+        if end_type == LineEndType::LineBreak {
+            self.next += 1;
+        } else {
+            *self = Self::default();
+        }
+    }
+}
+
+/// The maximum number of break opportunities that may fit in a single line.
+const ACTIVE_NODES: usize = 32;
+
+/// Upper limit of the badness of a line.
+const MAX_BADNESS: u64 = 10_000;
+
+/// Demerits added to every line, so that fewer lines are preferred.
+const LINE_PENALTY: u64 = 10;
+
+/// Demerits added when a line ends with a hyphen.
+const HYPHEN_DEMERITS: u64 = 50 * 50;
+
+/// A feasible line break.
+#[derive(Clone, Copy, Debug, Default)]
+struct Node {
+    /// Total width of the text before the first word of the next line.
+    width: u32,
+
+    /// Number of spaces before the first word of the next line.
+    spaces: u32,
+
+    /// The next line hasn't started yet, `width` and `spaces` are not known.
+    start_pending: bool,
+
+    /// The sum of the demerits of the lines that end at this break.
+    demerits: u64,
+
+    /// Natural widths of the first lines on the best path to this break.
+    lines: [u16; PLANNED_LINES],
+
+    /// The number of lines on the best path to this break.
+    line_count: usize,
+}
+
+/// A break opportunity.
+struct Candidate {
+    /// Total width of the text before the break.
+    width: u32,
+
+    /// Number of spaces before the break.
+    spaces: u32,
+
+    /// Width of the characters displayed at the end of the line, i.e. a hyphen.
+    break_width: u32,
+
+    /// Additional demerits.
+    demerits: u64,
+
+    /// The last line of the paragraph is not justified, so it can't be too loose.
+    last: bool,
+}
+
+struct TotalFit {
+    max_line_width: u32,
+    space_width: u32,
+    active: [Node; ACTIVE_NODES],
+    active_count: usize,
+}
+
+impl TotalFit {
+    fn new(max_line_width: u32, space_width: u32, leading_spaces: bool) -> Self {
+        let mut active = [Node::default(); ACTIVE_NODES];
+        active[0].start_pending = !leading_spaces;
+
+        Self {
+            max_line_width,
+            space_width,
+            active,
+            active_count: 1,
+        }
+    }
+
+    fn badness(&self, slack: u32, spaces: u32) -> u64 {
+        let stretch = (spaces * self.space_width) as u64;
+        if slack == 0 {
+            return 0;
+        }
+
+        match (slack as u64 * 100).checked_div(stretch) {
+            Some(ratio) => {
+                // Limit the ratio to avoid overflow, badness is capped anyway.
+                let ratio = ratio.min(1_000);
+                (ratio * ratio * ratio / 10_000).min(MAX_BADNESS)
+            }
+            None => MAX_BADNESS,
+        }
+    }
+
+    /// Marks the start of the next line for all active breaks that don't have one yet.
+    fn start_line(&mut self, width: u32, spaces: u32) {
+        for node in self.active[..self.active_count].iter_mut() {
+            if node.start_pending {
+                node.width = width;
+                node.spaces = spaces;
+                node.start_pending = false;
+            }
+        }
+    }
+
+    /// Finds the best line ending at `candidate` and removes breaks that are too far behind.
+    ///
+    /// Returns `Err` if a word doesn't fit in a line, or the memory budget is exceeded.
+    fn add_candidate(&mut self, candidate: Candidate) -> Result<Option<Node>, ()> {
+        let mut best: Option<Node> = None;
+
+        let mut idx = 0;
+        while idx < self.active_count {
+            let node = self.active[idx];
+            if node.start_pending {
+                idx += 1;
+                continue;
+            }
+
+            let content_width = candidate.width - node.width;
+            if content_width > self.max_line_width {
+                // Text only gets longer, this break can't start any more lines.
+                self.active_count -= 1;
+                self.active[idx] = self.active[self.active_count];
+                continue;
+            }
+            idx += 1;
+
+            let natural_width = content_width + candidate.break_width;
+            if natural_width > self.max_line_width {
+                continue;
+            }
+
+            let badness = if candidate.last {
+                0
+            } else {
+                self.badness(
+                    self.max_line_width - natural_width,
+                    candidate.spaces - node.spaces,
+                )
+            };
+            let line_demerits = (LINE_PENALTY + badness) * (LINE_PENALTY + badness);
+            let demerits = node.demerits + line_demerits + candidate.demerits;
+
+            if best.map_or(true, |best| demerits < best.demerits) {
+                let mut lines = node.lines;
+                if let Some(line) = lines.get_mut(node.line_count) {
+                    // Fits, because `natural_width` is at most `max_line_width`.
+                    *line = natural_width as u16;
+                }
+
+                best = Some(Node {
+                    width: 0,
+                    spaces: 0,
+                    start_pending: true,
+                    demerits,
+                    lines,
+                    line_count: node.line_count + 1,
+                });
+            }
+        }
+
+        if self.active_count == 0 {
+            return Err(());
+        }
+
+        if let Some(node) = best {
+            if self.active_count == ACTIVE_NODES {
+                return Err(());
+            }
+
+            self.active[self.active_count] = node;
+            self.active_count += 1;
+        }
+
+        Ok(best)
+    }
+
+    /// Returns the widths of the first lines of the best way to break the rest of the paragraph.
+    ///
+    /// The whole paragraph is read, up to `max_breakpoints` break opportunities.
+    fn line_widths<'a, S, M>(
+        &mut self,
+        plugin: &PluginWrapper<'a, M, S::Color>,
+        character_style: &S,
+        parser: &Parser<'a, S::Color>,
+        max_breakpoints: u16,
+    ) -> Option<LineWidths>
+    where
+        S: TextRenderer,
+        M: Plugin<'a, S::Color>,
+    {
+        if self.max_line_width > u16::MAX as u32 {
+            return None;
+        }
+
+        let lookahead = plugin.clone();
+        let mut lookahead_parser = parser.clone();
+
+        let mut width = 0;
+        let mut spaces = 0;
+
+        // Spaces after the last word are not part of the line if it's broken there.
+        let mut trailing_width = 0;
+        let mut trailing_spaces = 0;
+
+        let mut breakpoints = 0;
+
+        loop {
+            let token = lookahead.peek_token(&mut lookahead_parser);
+            lookahead.consume_peeked_token();
+
+            let candidate = match token {
+                Some(Token::Word(w)) => {
+                    self.start_line(width, spaces);
+
+                    width += str_width(character_style, w);
+                    spaces += w.chars().filter(|c| c.is_whitespace()).count() as u32;
+                    trailing_width = 0;
+                    trailing_spaces = 0;
+                    continue;
+                }
+
+                Some(Token::Whitespace(n, _)) => {
+                    let candidate = Candidate {
+                        width: width - trailing_width,
+                        spaces: spaces - trailing_spaces,
+                        break_width: 0,
+                        demerits: 0,
+                        last: false,
+                    };
+
+                    width += n * self.space_width;
+                    spaces += n;
+                    trailing_width += n * self.space_width;
+                    trailing_spaces += n;
+
+                    candidate
+                }
+
+                Some(Token::Break(c)) => Candidate {
+                    width: width - trailing_width,
+                    spaces: spaces - trailing_spaces,
+                    break_width: str_width(character_style, c),
+                    demerits: if c.is_empty() { 0 } else { HYPHEN_DEMERITS },
+                    last: false,
+                },
+
+                Some(Token::InlineObject { size, .. }) => {
+                    self.start_line(width, spaces);
+
+                    width += size.width;
+                    trailing_width = 0;
+                    trailing_spaces = 0;
+                    continue;
+                }
+
+                Some(Token::ChangeTextStyle(_)) => continue,
+
+                Some(Token::NewLine) | None => Candidate {
+                    width: width - trailing_width,
+                    spaces: spaces - trailing_spaces,
+                    break_width: 0,
+                    demerits: 0,
+                    last: true,
+                },
+
+                // Can't predict the width of these.
+                Some(Token::Tab) | Some(Token::CarriageReturn) | Some(Token::MoveCursor { .. }) => {
+                    return None
+                }
+            };
+
+            if candidate.last {
+                return self
+                    .add_candidate(candidate)
+                    .ok()
+                    .flatten()
+                    .map(|node| LineWidths {
+                        max_line_width: self.max_line_width,
+                        widths: node.lines,
+                        len: node.line_count.min(PLANNED_LINES),
+                        next: 0,
+                        greedy: false,
+                    });
+            }
+
+            breakpoints += 1;
+            if breakpoints > max_breakpoints || self.add_candidate(candidate).is_err() {
+                return None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        alignment::HorizontalAlignment,
+        style::{test::assert_line_widths, ParagraphBreaking, TextBoxStyleBuilder},
+    };
+
+    #[track_caller]
+    fn assert_total_fit(text: &str, max_chars: u32, expected: &[u32]) {
+        let style = TextBoxStyleBuilder::new()
+            .alignment(HorizontalAlignment::Justified)
+            .paragraph_breaking(ParagraphBreaking::TotalFit {
+                max_breakpoints: 100,
+            })
+            .build();
+
+        assert_line_widths(style, text, max_chars, expected);
+    }
+
+    #[test]
+    fn greedy_breaking_is_used_when_it_is_optimal() {
+        assert_total_fit("aaa bbb ccc", 7, &[7, 3]);
+    }
+
+    #[test]
+    fn total_fit_avoids_loose_lines() {
+        // Greedy breaking results in a very loose second line:
+        // |at a b c|
+        // |quick   |
+        // |brown   |
+        // Total fit moves the "c" to the second line:
+        // |at a b  |
+        // |c quick |
+        // |brown   |
+        assert_total_fit("at a b c quick brown", 8, &[6, 7, 5]);
+    }
+
+    #[test]
+    fn long_paragraph_is_broken_in_multiple_passes() {
+        // The line breaks are planned for 8 lines at a time.
+        assert_total_fit(
+            "at a b c quick brown at a b c quick brown at a b c quick brown at a b c quick brown",
+            8,
+            &[6, 7, 8, 3, 7, 8, 3, 7, 8, 3, 7, 5],
+        );
+    }
+}
@@ -0,0 +1,51 @@
+//! Text direction options.
+
+/// Text direction options.
+///
+/// The text direction sets the base direction of paragraphs. It decides how bidirectional text is
+/// reordered, and which side of the text box the [`Start`] and [`End`] alignments refer to.
+///
+/// Bidirectional text support requires the `bidi` feature. Text is always broken into lines in
+/// logical order, then each line is reordered for display according to the Unicode Bidirectional
+/// Algorithm (UAX #9). Only implicit embedding levels are supported: explicit directional
+/// embeddings, overrides and isolates are ignored.
+///
+/// The bidirectional character types are looked up in a built-in table that covers the Hebrew,
+/// Arabic, Syriac, Thaana and NKo scripts, common punctuation and symbols. Other characters are
+/// treated as left-to-right. A line is split into at most 16 runs of text with different
+/// directions. If a line contains more runs, the text after the start of the 16th run is
+/// displayed in the direction of that run.
+///
+/// [`Start`]: crate::alignment::HorizontalAlignment::Start
+/// [`End`]: crate::alignment::HorizontalAlignment::End
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum TextDirection {
+    /// Left-to-right paragraphs.
+    ///
+    /// With the `bidi` feature enabled, right-to-left runs of text (e.g. Hebrew or Arabic words)
+    /// are reordered. Without the feature, text is displayed in logical order.
+    ///
+    /// This is the default setting.
+    LeftToRight,
+
+    /// Right-to-left paragraphs.
+    ///
+    /// This mode requires the `bidi` feature.
+    #[cfg(feature = "bidi")]
+    RightToLeft,
+
+    /// The direction of each paragraph is determined by its first strongly directional
+    /// character. Paragraphs without such characters are left-to-right.
+    ///
+    /// This mode requires the `bidi` feature.
+    #[cfg(feature = "bidi")]
+    Auto,
+}
+
+impl TextDirection {
+    /// Returns the default text direction, which is [`TextDirection::LeftToRight`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::LeftToRight
+    }
+}
@@ -0,0 +1,120 @@
+//! Text overflow options.
+
+use embedded_graphics::text::renderer::TextRenderer;
+
+use crate::utils::{graphemes, str_width};
+
+/// Text overflow options.
+///
+/// The text overflow mode decides how the text box indicates that the text doesn't fit into it.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum TextOverflow {
+    /// Text that doesn't fit into the text box is cut off.
+    ///
+    /// This is the default setting.
+    Clip,
+
+    /// Replace the end of the last visible line with the given string, e.g. `"…"` or `"..."`.
+    ///
+    /// The string is displayed using the active character style, and the last line is shortened
+    /// so that the string fits into the text box.
+    Ellipsis(&'static str),
+
+    /// Replace the middle of the text with the given string if it doesn't fit into a single
+    /// line.
+    ///
+    /// This mode is meant for single line text boxes that display file paths, device names and
+    /// similar text where both the start and the end of the text are important. The text is not
+    /// wrapped. Text that contains line breaks is displayed as if [`TextOverflow::Clip`] was
+    /// used.
+    ///
+    /// *Note:* elided text is displayed without processing plugins and special characters.
+    MiddleEllipsis(&'static str),
+}
+
+impl TextOverflow {
+    /// Returns the default text overflow mode, which is [`TextOverflow::Clip`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::Clip
+    }
+
+    /// Returns the string that replaces the end of the last visible line.
+    pub(crate) const fn ellipsis(self) -> Option<&'static str> {
+        match self {
+            TextOverflow::Ellipsis(ellipsis) => Some(ellipsis),
+            TextOverflow::Clip | TextOverflow::MiddleEllipsis(_) => None,
+        }
+    }
+
+    /// Splits the text into a start and an end part if the middle of the text should be elided.
+    pub(crate) fn elide_middle<'a>(
+        self,
+        renderer: &impl TextRenderer,
+        text: &'a str,
+        max_line_width: u32,
+    ) -> Option<ElidedText<'a>> {
+        let ellipsis = match self {
+            TextOverflow::MiddleEllipsis(ellipsis) => ellipsis,
+            TextOverflow::Clip | TextOverflow::Ellipsis(_) => return None,
+        };
+
+        if text.contains(['\n', '\r']) || str_width(renderer, text) <= max_line_width {
+            return None;
+        }
+
+        let available = max_line_width.saturating_sub(str_width(renderer, ellipsis));
+
+        // The start of the text gets the extra pixel if the available space can't be halved.
+        let start_len = longest_prefix(renderer, text, (available + 1) / 2);
+        let start = &text[..start_len];
+        let end_len = longest_suffix(
+            renderer,
+            &text[start_len..],
+            available - str_width(renderer, start),
+        );
+        let end = &text[text.len() - end_len..];
+
+        Some(ElidedText {
+            start: start.trim_end(),
+            ellipsis,
+            end: end.trim_start(),
+        })
+    }
+}
+
+/// Text that is displayed with its middle part replaced.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ElidedText<'a> {
+    pub start: &'a str,
+    pub ellipsis: &'static str,
+    pub end: &'a str,
+}
+
+impl ElidedText<'_> {
+    /// Returns the width of the displayed text.
+    pub fn width(&self, renderer: &impl TextRenderer) -> u32 {
+        str_width(renderer, self.start)
+            + str_width(renderer, self.ellipsis)
+            + str_width(renderer, self.end)
+    }
+}
+
+/// Returns the length in bytes of the longest prefix that fits into `width`.
+fn longest_prefix(renderer: &impl TextRenderer, text: &str, width: u32) -> usize {
+    graphemes(text)
+        .map(|(idx, grapheme)| idx + grapheme.len())
+        .take_while(|&end| str_width(renderer, &text[..end]) <= width)
+        .last()
+        .unwrap_or(0)
+}
+
+/// Returns the length in bytes of the longest suffix that fits into `width`.
+fn longest_suffix(renderer: &impl TextRenderer, text: &str, width: u32) -> usize {
+    graphemes(text)
+        .rev()
+        .map(|(idx, _)| text.len() - idx)
+        .take_while(|&len| str_width(renderer, &text[text.len() - len..]) <= width)
+        .last()
+        .unwrap_or(0)
+}
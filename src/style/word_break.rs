@@ -0,0 +1,71 @@
+//! Word breaking options.
+
+/// Word breaking options.
+///
+/// The word breaking mode decides what happens to words that don't fit into the remaining space
+/// of a line.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+pub enum WordBreak {
+    /// Move words that don't fit to the next line. Words that are longer than a line are broken
+    /// at the last character that fits.
+    ///
+    /// This is the default setting.
+    BreakWord,
+
+    /// Break words at the last character that fits into the line, even if the word would fit
+    /// into the next line.
+    BreakAll,
+
+    /// Never break words. Words that are longer than a line are placed on a separate line and
+    /// are cut off at the edge of the text box.
+    KeepAll,
+
+    /// Words that are longer than a line are not moved to the next line, but are cut off at the
+    /// edge of the text box. The rest of the word is not displayed.
+    Clip,
+}
+
+impl WordBreak {
+    /// Returns the default word breaking mode, which is [`WordBreak::BreakWord`].
+    #[inline]
+    pub const fn default() -> Self {
+        Self::BreakWord
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::style::{test::assert_line_widths, TextBoxStyle, WordBreak};
+
+    #[track_caller]
+    fn assert_word_break(word_break: WordBreak, text: &str, max_chars: u32, expected: &[u32]) {
+        assert_line_widths(
+            TextBoxStyle::with_word_break(word_break),
+            text,
+            max_chars,
+            expected,
+        );
+    }
+
+    #[test]
+    fn break_word() {
+        assert_word_break(WordBreak::BreakWord, "ab abcdefgh", 5, &[2, 5, 3]);
+    }
+
+    #[test]
+    fn break_all() {
+        assert_word_break(WordBreak::BreakAll, "ab abcdefgh", 5, &[5, 5, 1]);
+    }
+
+    #[test]
+    fn keep_all() {
+        assert_word_break(WordBreak::KeepAll, "ab abcdefgh", 5, &[2, 8]);
+        assert_word_break(WordBreak::KeepAll, "abcdefgh ab", 5, &[8, 2]);
+    }
+
+    #[test]
+    fn clip() {
+        assert_word_break(WordBreak::Clip, "ab abcdefgh", 5, &[11]);
+        assert_word_break(WordBreak::Clip, "ab abc abcdefgh ab", 5, &[2, 12, 2]);
+    }
+}
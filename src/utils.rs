@@ -26,6 +26,49 @@ pub fn str_width_and_left_offset(renderer: &impl TextRenderer, s: &str) -> (u32,
     )
 }
 
+/// Returns the user-perceived characters of a string, together with their byte offsets.
+///
+/// With the `grapheme` feature enabled, the string is split into extended grapheme clusters.
+/// Otherwise, every `char` is returned separately.
+#[cfg(feature = "grapheme")]
+pub(crate) fn graphemes(s: &str) -> impl DoubleEndedIterator<Item = (usize, &str)> {
+    unicode_segmentation::UnicodeSegmentation::grapheme_indices(s, true)
+}
+
+/// Returns the user-perceived characters of a string, together with their byte offsets.
+///
+/// With the `grapheme` feature enabled, the string is split into extended grapheme clusters.
+/// Otherwise, every `char` is returned separately.
+#[cfg(not(feature = "grapheme"))]
+pub(crate) fn graphemes(s: &str) -> impl DoubleEndedIterator<Item = (usize, &str)> {
+    s.char_indices()
+        .map(move |(idx, c)| (idx, &s[idx..idx + c.len_utf8()]))
+}
+
+/// Splits a string into grapheme clusters and their widths.
+///
+/// The width of the last grapheme is adjusted so that the sum of the widths is `width`.
+pub(crate) fn grapheme_widths<'s, S: TextRenderer>(
+    renderer: &'s S,
+    st: &'s str,
+    width: u32,
+) -> impl Iterator<Item = (&'s str, u32)> + 's {
+    let mut remaining = width;
+    let mut iter = graphemes(st).peekable();
+
+    core::iter::from_fn(move || {
+        let (_, grapheme) = iter.next()?;
+        let width = if iter.peek().is_some() {
+            str_width(renderer, grapheme).min(remaining)
+        } else {
+            remaining
+        };
+        remaining -= width;
+
+        Some((grapheme, width))
+    })
+}
+
 #[cfg(test)]
 pub mod test {
     use az::SaturatingAs;
@@ -222,6 +265,70 @@ where {
         }
     }
 
+    /// A [`TestFont`] that is measured using the bounding boxes of its glyphs.
+    ///
+    /// The glyphs are 7 pixels tall, only the tail of the 'j' reaches the bottom of the line.
+    #[derive(Copy, Clone)]
+    pub struct InkTestFont<C>(pub TestFont<C>);
+
+    fn glyph_height(c: char) -> u32 {
+        match c {
+            'j' => 10,
+            _ => 7,
+        }
+    }
+
+    impl<C> CharacterStyle for InkTestFont<C>
+    where
+        C: PixelColor,
+    {
+        type Color = C;
+    }
+
+    impl<C> TextRenderer for InkTestFont<C>
+    where
+        C: PixelColor,
+    {
+        type Color = C;
+
+        fn draw_string<D>(
+            &self,
+            text: &str,
+            position: Point,
+            baseline: Baseline,
+            target: &mut D,
+        ) -> Result<Point, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color>,
+        {
+            self.0.draw_string(text, position, baseline, target)
+        }
+
+        fn draw_whitespace<D>(
+            &self,
+            width: u32,
+            position: Point,
+            baseline: Baseline,
+            target: &mut D,
+        ) -> Result<Point, D::Error>
+        where
+            D: DrawTarget<Color = Self::Color>,
+        {
+            self.0.draw_whitespace(width, position, baseline, target)
+        }
+
+        fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+            let mut metrics = self.0.measure_string(text, position, baseline);
+            metrics.bounding_box.size.height = text.chars().map(glyph_height).max().unwrap_or(0);
+
+            metrics
+        }
+
+        fn line_height(&self) -> u32 {
+            self.0.line_height()
+        }
+    }
+
     #[test]
     fn glyph_j_has_negative_left_side_bearing() {
         let font = TestFont::new(BinaryColor::On, BinaryColor::Off);